@@ -0,0 +1,119 @@
+//! Actions applied to every marked row at once (see `v` / `V` in the Todos tab).
+//! Each one is a single undo step and reports counts in the status line.
+
+use crate::app::App;
+
+/// Toggle done on every marked task; blocked ones are skipped
+pub fn toggle(app: &mut App) {
+    let ids = app.marked_ids();
+    let before = app.list.clone();
+    let (mut toggled, mut blocked) = (0, 0);
+    for id in &ids {
+        let Some(idx) = app.list.find_index_by_id(id) else { continue };
+        if app.list.toggle_done_index(idx) {
+            toggled += 1;
+        } else {
+            blocked += 1;
+        }
+    }
+    app.status_line = format!("Toggled {toggled} of {} marked", ids.len());
+    if blocked > 0 {
+        app.status_line.push_str(&format!(" · {blocked} blocked"));
+    }
+    if toggled > 0 {
+        app.record(format!("Toggle {toggled} tasks"), before);
+    }
+}
+
+/// Delete every marked task; unmarked subtasks move up to the nearest kept parent
+pub fn delete(app: &mut App) {
+    let ids = app.marked_ids();
+    let before = app.list.clone();
+    let mut deleted = 0;
+    for id in &ids {
+        if let Some(idx) = app.list.find_index_by_id(id)
+            && app.list.delete_index(idx)
+        {
+            deleted += 1;
+        }
+    }
+    app.clear_marks();
+    app.status_line = format!("Moved {deleted} tasks to trash ✓ (u to undo)");
+    if deleted > 0 {
+        app.record(format!("Delete {deleted} tasks"), before);
+    }
+}
+
+/// Raise (`delta < 0`) or lower the priority of every marked task
+pub fn shift_priority(app: &mut App, delta: i8) {
+    let ids = app.marked_ids();
+    let before = app.list.clone();
+    let mut changed = 0;
+    for id in &ids {
+        let Some(idx) = app.list.find_index_by_id(id) else { continue };
+        let t = &mut app.list.items[idx];
+        let p = (t.priority + delta).clamp(1, 5);
+        if p != t.priority {
+            t.priority = p;
+            changed += 1;
+        }
+    }
+    app.status_line = format!("Priority {} on {changed} of {} marked", if delta < 0 { "raised" } else { "lowered" }, ids.len());
+    if changed > 0 {
+        app.record(format!("Change priority of {changed} tasks"), before);
+    }
+}
+
+/// Add `+tag` (or remove `-tag`) on every marked task
+pub fn tag(app: &mut App, input: &str) {
+    let input = input.trim();
+    let (remove, tag) = match input.strip_prefix('-') {
+        Some(t) => (true, t),
+        None => (false, input.trim_start_matches('+')),
+    };
+    if tag.is_empty() || tag.contains(char::is_whitespace) {
+        app.status_line = "Tag must be a single word, e.g. +urgent or -urgent".into();
+        return;
+    }
+    let ids = app.marked_ids();
+    let before = app.list.clone();
+    let mut changed = 0;
+    for id in &ids {
+        let Some(idx) = app.list.find_index_by_id(id) else { continue };
+        let t = &mut app.list.items[idx];
+        if remove && t.has_tag(tag) {
+            t.tags.retain(|x| !x.eq_ignore_ascii_case(tag));
+            changed += 1;
+        } else if !remove && !t.has_tag(tag) {
+            t.tags.push(tag.to_string());
+            changed += 1;
+        }
+    }
+    let verb = if remove { "Untagged" } else { "Tagged" };
+    app.status_line = format!("{verb} {changed} of {} marked ({}{tag})", ids.len(), if remove { "-" } else { "+" });
+    if changed > 0 {
+        app.record(format!("{verb} {changed} tasks"), before);
+    }
+}
+
+/// Move every marked task one step up or down among its siblings
+pub fn move_marked(app: &mut App, delta: isize) {
+    let mut ids = app.marked_ids();
+    // move the leading task first so marked neighbours don't swap with each other
+    if delta > 0 {
+        ids.reverse();
+    }
+    let before = app.list.clone();
+    let mut moved = 0;
+    for id in &ids {
+        if let Some(idx) = app.list.find_index_by_id(id)
+            && app.list.move_by(idx, delta)
+        {
+            moved += 1;
+        }
+    }
+    app.status_line = format!("Moved {moved} of {} marked {}", ids.len(), if delta < 0 { "up" } else { "down" });
+    if moved > 0 {
+        app.record(format!("Move {moved} tasks"), before);
+    }
+}
@@ -0,0 +1,174 @@
+//! The `?` overlay: every binding for the current tab and input mode, grouped
+//! and searchable. Entries come from the same tables the key handlers read:
+//! the keymap for normal mode, `INSERT_KEYS` and `EDIT_KEYS` for the form, and
+//! `PromptAction::table` for search, the status picker and the other prompts.
+
+use std::cell::Cell;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::app::{
+    keymap::{format_keys, Action, Group, PromptAction, INSERT_KEYS},
+    textinput::EDIT_KEYS,
+    trash, App, InputMode,
+};
+
+#[derive(Debug, Clone)]
+pub struct Help {
+    pub query: String,
+    pub scroll: u16,
+    /// Set while drawing, like `App::notes_scroll_max`
+    pub scroll_max: Cell<u16>,
+    /// Whose keys are listed, and where Esc goes back to
+    pub return_to: InputMode,
+}
+
+impl Default for Help {
+    fn default() -> Self {
+        Help { query: String::new(), scroll: 0, scroll_max: Cell::new(0), return_to: InputMode::Normal }
+    }
+}
+
+/// One line of the overlay
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub group: &'static str,
+    pub keys: String,
+    pub label: &'static str,
+}
+
+pub fn open(app: &mut App) {
+    app.help = Help { return_to: app.input_mode, ..Help::default() };
+    app.input_mode = InputMode::Help;
+}
+
+fn close(app: &mut App) {
+    app.input_mode = app.help.return_to;
+    app.help.query.clear();
+}
+
+/// What the overlay is listing, for its title
+pub fn context(app: &App) -> &'static str {
+    match (app.help.return_to, PromptAction::table(app.help.return_to)) {
+        (InputMode::Insert, _) => "Add / edit form",
+        (_, Some((name, _))) => name,
+        _ => app.tabs.current().title(),
+    }
+}
+
+/// All bindings for the mode the overlay was opened from, in display order
+pub fn entries(app: &App) -> Vec<Entry> {
+    let mut out = Vec::new();
+    if app.help.return_to == InputMode::Insert {
+        for &(action, keys, label) in INSERT_KEYS {
+            let group = if action.priority_only() { "Priority field" } else { "Form" };
+            out.push(Entry { group, keys: format_keys(keys), label });
+        }
+        for &(_, keys, label) in EDIT_KEYS {
+            out.push(Entry { group: "Text fields", keys: format_keys(keys), label });
+        }
+        return out;
+    }
+    if let Some((group, table)) = PromptAction::table(app.help.return_to) {
+        for &(_, keys, label) in table {
+            out.push(Entry { group, keys: format_keys(keys), label });
+        }
+        return out;
+    }
+
+    let tab = app.tabs.current();
+    for group in Group::ALL.into_iter().filter(|g| g.shown_on(tab)) {
+        // the Trash tab gives some actions its own meaning
+        let actions: Vec<(Action, &'static str)> = if group == Group::Trash {
+            trash::ACTIONS.to_vec()
+        } else {
+            Action::ALL.iter().filter(|a| a.group() == group).map(|&a| (a, a.label())).collect()
+        };
+        for (action, label) in actions {
+            let keys = app.keymap.keys_for(action);
+            if !keys.is_empty() {
+                out.push(Entry { group: group.title(), keys, label });
+            }
+        }
+    }
+    out
+}
+
+/// Entries whose keys, description or group contain `query` (any case)
+pub fn matching(entries: Vec<Entry>, query: &str) -> Vec<Entry> {
+    let query = query.to_lowercase();
+    entries
+        .into_iter()
+        .filter(|e| [e.keys.as_str(), e.label, e.group].iter().any(|s| s.to_lowercase().contains(&query)))
+        .collect()
+}
+
+pub fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    let help = &mut app.help;
+    let max = help.scroll_max.get();
+    match key.code {
+        KeyCode::Esc if !help.query.is_empty() => help.query.clear(),
+        KeyCode::Esc | KeyCode::F(1) => close(app),
+        KeyCode::Char('?') if help.query.is_empty() => close(app),
+        KeyCode::Down => help.scroll = (help.scroll + 1).min(max),
+        KeyCode::Up => help.scroll = help.scroll.saturating_sub(1),
+        KeyCode::PageDown => help.scroll = (help.scroll + 10).min(max),
+        KeyCode::PageUp => help.scroll = help.scroll.saturating_sub(10),
+        KeyCode::Home => help.scroll = 0,
+        KeyCode::End => help.scroll = max,
+        KeyCode::Backspace => {
+            help.query.pop();
+            help.scroll = 0;
+        }
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            help.query.push(c);
+            help.scroll = 0;
+        }
+        _ => {}
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::Tab, todolist::TodoList};
+
+    #[test]
+    fn lists_bindings_for_the_tab_and_mode() {
+        let mut app = App::new(TodoList::default());
+        open(&mut app);
+        let todos = entries(&app);
+        assert!(todos.iter().any(|e| e.keys == "a" && e.label == "add" && e.group == "Tasks"));
+        assert!(!todos.iter().any(|e| e.group == "Trash"));
+        let found = matching(entries(&app), "SORT");
+        assert!(!found.is_empty() && found.iter().all(|e| e.group == "Order & sort" || e.label.contains("sort")));
+
+        handle_key(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        while app.tabs.current() != Tab::Trash {
+            app.tabs.next();
+        }
+        open(&mut app);
+        assert!(entries(&app).iter().any(|e| e.keys == "d" && e.label == "purge"));
+
+        app.help.return_to = InputMode::Insert;
+        let form = entries(&app);
+        assert!(form.iter().any(|e| e.group == "Priority field" && e.keys == "←/↑"));
+        assert!(form.iter().any(|e| e.group == "Text fields" && e.keys.contains("^w")));
+
+        app.help.return_to = InputMode::PickStatus;
+        assert!(entries(&app).iter().any(|e| e.keys == "1/2/3/4/5/6" && e.label == "set status by number"));
+    }
+
+    #[test]
+    fn opens_from_prompts_and_returns_to_them() {
+        let mut app = App::new(TodoList::default());
+        app.input_mode = InputMode::Search;
+        crate::app::input::handle_event(&mut app, crossterm::event::Event::Key(KeyEvent::from(KeyCode::F(1))));
+        assert_eq!(app.input_mode, InputMode::Help);
+        assert_eq!(context(&app), "Search");
+        handle_key(&mut app, KeyEvent::from(KeyCode::Esc));
+        assert_eq!(app.input_mode, InputMode::Search);
+    }
+}
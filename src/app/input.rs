@@ -1,66 +1,316 @@
 use crossterm::event::{Event, KeyCode, KeyEvent};
-use crate::app::{App, InputMode, InsertField, MapView};
+use crate::app::keymap::{self, Action, InsertAction, PromptAction, Resolved};
+use crate::app::{bulk, help, mouse, trash, App, InputMode, InsertField, MapView, Tab};
+use crate::recur::Recurrence;
+use crate::task::{inline_title, parse_inline_tokens, unix_now, Status};
+use crate::todolist::StatusFilter;
 
 pub fn handle_event(app: &mut App, ev: Event) -> bool {
     match ev {
-        Event::Key(KeyEvent { code, .. }) => match app.input_mode {
-            InputMode::Normal => handle_normal_mode(app, code),
-            InputMode::Insert => handle_insert_mode(app, code),
+        Event::Key(key) => match app.input_mode {
+            InputMode::Normal => handle_normal_mode(app, key),
+            InputMode::Insert => handle_insert_mode(app, key),
+            InputMode::ConfirmDelete => handle_confirm_delete(app, key),
+            InputMode::ConfirmBulkDelete => handle_confirm_bulk_delete(app, key),
+            InputMode::PickStatus => handle_pick_status(app, key),
+            InputMode::Search => handle_search(app, key),
+            InputMode::BulkTag => handle_bulk_tag(app, key),
+            InputMode::Help => help::handle_key(app, key),
         },
+        // bracketed paste goes into the focused text field
+        Event::Paste(text) => {
+            if app.input_mode == InputMode::Insert
+                && let Some(input) = app.active_input()
+            {
+                input.insert_str(&text);
+            }
+            true
+        }
+        Event::Mouse(mouse) => {
+            mouse::handle_mouse(app, mouse);
+            true
+        }
         Event::Resize(_, _) => true,
         _ => true,
     }
 }
 
-fn handle_normal_mode(app: &mut App, code: KeyCode) -> bool {
-    match code {
-        KeyCode::Char('q') => return false,
+/// Normal-mode keys go through the keymap (see `keymap.rs`), which also handles chords
+fn handle_normal_mode(app: &mut App, key: KeyEvent) -> bool {
+    let action = match app.keymap.resolve(&mut app.pending_keys, key.into()) {
+        Resolved::Action(action) => action,
+        Resolved::Pending => {
+            app.status_line = format!("{} …", keymap::format_sequence(&app.pending_keys));
+            return true;
+        }
+        Resolved::Unbound => return true,
+    };
+    if app.tabs.current() == Tab::Trash && trash::handle_action(app, action) {
+        return true;
+    }
+    match action {
+        Action::Quit => return false,
+        Action::Help => help::open(app),
 
-        KeyCode::Down => app.select_next(),
-        KeyCode::Up => app.select_prev(),
+        Action::SelectNext => app.select_next(),
+        Action::SelectPrev => app.select_prev(),
+        Action::SelectFirst => {
+            app.selected = 0;
+            app.notes_scroll = 0;
+        }
+        Action::SelectLast => {
+            app.selected = app.visible_indices().len().saturating_sub(1);
+            app.notes_scroll = 0;
+        }
 
-        KeyCode::Char('a') => {
+        Action::Add | Action::AddSubtask => {
+            // a subtask goes under the selected task
+            app.insert_parent = match action {
+                Action::AddSubtask => app.selected_index().map(|i| app.list.items[i].id.clone()),
+                _ => None,
+            };
+            app.editing = None;
             app.input_mode = InputMode::Insert;
             app.insert_field = InsertField::Title;
             app.draft_title.clear();
-            app.draft_priority = 1;
+            app.draft_priority = app.default_priority;
             app.draft_notes.clear();
             app.draft_timeframe.clear();
+            app.draft_recurrence.clear();
             app.status_line.clear();
         }
 
+        // Edit the selected task in the same overlay, pre-filled
+        Action::Edit => {
+            if let Some(real_idx) = app.selected_index() {
+                let t = &app.list.items[real_idx];
+                app.draft_title.set(inline_title(t));
+                app.draft_notes.set(t.notes.clone().unwrap_or_default());
+                app.draft_timeframe.set(t.timeframe.clone().unwrap_or_default());
+                app.draft_recurrence.set(t.recurrence.as_ref().map(|r| r.to_string()).unwrap_or_default());
+                app.draft_priority = t.priority;
+                app.editing = Some(t.id.clone());
+                app.insert_parent = None;
+                app.insert_field = InsertField::Title;
+                app.input_mode = InputMode::Insert;
+                app.status_line.clear();
+            }
+        }
+
+        // Scroll long notes in the expanded panel
+        Action::ScrollNotesDown if app.expanded => app.scroll_notes(5),
+        Action::ScrollNotesUp if app.expanded => app.scroll_notes(-5),
+
+        // Open the selected task's notes in $VISUAL / $EDITOR
+        Action::EditNotes => {
+            app.notes_editor = app.selected_id();
+        }
+
         // Expand/collapse inline details with Space
-        KeyCode::Char(' ') => {
+        Action::ToggleExpand => {
             app.expanded = !app.expanded;
             app.status_line = if app.expanded { "Expanded".into() } else { "Collapsed".into() };
         }
 
-        // Toggle done on Enter
-        KeyCode::Enter => {
-            if let Some(real_idx) = app.visible_indices().get(app.selected).cloned() {
-                if app.list.toggle_done_index(real_idx) {
-                    app.status_line = "Toggled ✓".into();
-                    app.dirty = true;
+        // Marks: `v` marks the row and steps down, `V` starts/ends a range, Esc clears
+        Action::Mark => {
+            if let Some(id) = app.selected_id() {
+                if !app.marked.remove(&id) {
+                    app.marked.insert(id);
                 }
+                app.select_next();
+                app.status_line = marked_status(app);
+            }
+        }
+        Action::MarkRange => {
+            if app.mark_anchor.is_some() {
+                let range = app.marked_ids();
+                app.marked.extend(range);
+                app.mark_anchor = None;
+            } else {
+                app.mark_anchor = app.selected_id();
+            }
+            app.status_line = marked_status(app);
+        }
+        Action::Cancel if app.has_marks() => {
+            app.clear_marks();
+            app.status_line = "Marks cleared".into();
+        }
+
+        // Bulk actions on marked rows
+        Action::ToggleDone if app.has_marks() => bulk::toggle(app),
+        Action::Delete if app.has_marks() => app.input_mode = InputMode::ConfirmBulkDelete,
+        Action::MoveUp if app.has_marks() => {
+            app.ensure_manual_sort();
+            bulk::move_marked(app, -1);
+        }
+        Action::MoveDown if app.has_marks() => {
+            app.ensure_manual_sort();
+            bulk::move_marked(app, 1);
+        }
+        Action::RaisePriority if app.has_marks() => bulk::shift_priority(app, -1),
+        Action::LowerPriority if app.has_marks() => bulk::shift_priority(app, 1),
+        Action::TagMarked if app.has_marks() => {
+            app.draft_tag.clear();
+            app.input_mode = InputMode::BulkTag;
+        }
+
+        // Toggle done; the tree variant also applies it to every subtask
+        Action::ToggleDone => toggle_selected(app),
+        Action::ToggleTree => {
+            if let Some(real_idx) = app.selected_index() {
+                let before = app.list.clone();
+                if app.list.toggle_done_cascade(real_idx) {
+                    let n = app.list.descendants(real_idx).len();
+                    app.status_line = format!("Toggled ✓ (with {n} subtasks)");
+                    app.record(format!("Toggle \"{}\" with subtasks", app.list.items[real_idx].title), before);
+                } else {
+                    app.status_line = blocked_status(app, real_idx);
+                }
+            }
+        }
+
+        // Pick any lifecycle status for the selected task
+        Action::PickStatus => {
+            if let Some(real_idx) = app.selected_index() {
+                let current = app.list.items[real_idx].status;
+                app.status_pick = Status::ALL.iter().position(|&s| s == current).unwrap_or(0);
+                app.input_mode = InputMode::PickStatus;
             }
         }
 
-        // Delete
-        KeyCode::Char('d') => {
-            if let Some(real_idx) = app.visible_indices().get(app.selected).cloned() {
-                if app.list.delete_index(real_idx) {
-                    app.status_line = "Deleted ✓".into();
-                    app.dirty = true;
-                    app.clamp_selection();
+        // Dependencies: `b` on the blocker, then `b` on the task that waits for it
+        Action::LinkBlocker => {
+            if let Some(real_idx) = app.selected_index() {
+                let picked = app.link_from.take().and_then(|id| app.list.find_index_by_id(&id));
+                match picked {
+                    None => {
+                        app.link_from = Some(app.list.items[real_idx].id.clone());
+                        app.status_line = format!("Blocker: {} — select the task it blocks and press b (Esc cancels)", app.list.items[real_idx].title);
+                    }
+                    Some(blocker) => {
+                        let before = app.list.clone();
+                        match app.list.add_dependency(real_idx, blocker) {
+                            Ok(()) => {
+                                let (task, blocker) = (&app.list.items[real_idx].title, &app.list.items[blocker].title);
+                                app.status_line = format!("{task} now waits for {blocker} ✓");
+                                let label = format!("Link \"{task}\" to \"{blocker}\"");
+                                app.record(label, before);
+                            }
+                            Err(e) => app.status_line = format!("Cannot link: {e}"),
+                        }
+                    }
                 }
             }
         }
+        Action::Cancel if app.link_from.is_some() => {
+            app.link_from = None;
+            app.status_line = "Link cancelled".into();
+        }
+
+        // Collapse / expand the selected subtree
+        Action::Fold => {
+            if let Some(real_idx) = app.selected_index()
+                && app.list.has_children(real_idx)
+            {
+                let id = app.list.items[real_idx].id.clone();
+                if !app.collapsed.remove(&id) {
+                    app.collapsed.insert(id);
+                    app.status_line = "Subtasks collapsed".into();
+                } else {
+                    app.status_line = "Subtasks expanded".into();
+                }
+            }
+        }
+
+        // Delete; parents ask what to do with their subtasks first
+        Action::Delete => {
+            if let Some(real_idx) = app.selected_index() {
+                if app.list.has_children(real_idx) {
+                    app.pending_delete = Some(app.list.items[real_idx].id.clone());
+                    app.input_mode = InputMode::ConfirmDelete;
+                } else {
+                    let before = app.list.clone();
+                    let label = format!("Delete \"{}\"", app.list.items[real_idx].title);
+                    if app.list.delete_index(real_idx) {
+                        app.status_line = "Moved to trash ✓ (u to undo)".into();
+                        app.record(label, before);
+                        app.clamp_selection();
+                    }
+                }
+            }
+        }
+
+        // Cycle tag / project filters through the values in use
+        Action::FilterTag => {
+            let prev = app.selected_id();
+            let next = cycle(&app.list.all_tags(), app.filter.tags.first());
+            app.filter.tags = next.into_iter().collect();
+            app.keep_selection(prev);
+            app.status_line = filter_status(app);
+        }
+        Action::FilterProject => {
+            let prev = app.selected_id();
+            app.filter.project = cycle(&app.list.all_projects(), app.filter.project.as_ref());
+            app.keep_selection(prev);
+            app.status_line = filter_status(app);
+        }
+
+        // Incremental fuzzy search over title, notes and timeframe
+        Action::Search => {
+            app.input_mode = InputMode::Search;
+            app.status_line = "Search: type to filter · Enter keep · Esc clear".into();
+        }
+
+        // Quick filters: `f` cycles all → pending → done, 1-5 show P1..Pn, 0 clears everything
+        Action::FilterStatus => {
+            let prev = app.selected_id();
+            app.filter.status = match app.filter.status {
+                None => Some(StatusFilter::Pending),
+                Some(StatusFilter::Pending) => Some(StatusFilter::Done),
+                Some(StatusFilter::Done) => None,
+            };
+            app.keep_selection(prev);
+            app.status_line = filter_status(app);
+        }
+        Action::MaxPriority1 | Action::MaxPriority2 | Action::MaxPriority3 | Action::MaxPriority4 | Action::MaxPriority5 => {
+            let prev = app.selected_id();
+            let hi = action.max_priority().unwrap_or(5);
+            app.filter.priority = if app.filter.priority == Some((1, hi)) { None } else { Some((1, hi)) };
+            app.keep_selection(prev);
+            app.status_line = filter_status(app);
+        }
+        Action::ClearFilters => {
+            let prev = app.selected_id();
+            app.filter = Default::default();
+            app.keep_selection(prev);
+            app.status_line = filter_status(app);
+        }
+
+        // Reorder by hand
+        Action::MoveUp => move_selected(app, -1),
+        Action::MoveDown => move_selected(app, 1),
+
+        // Sort: cycle the key or flip the direction
+        Action::CycleSort | Action::FlipSort => {
+            let prev = app.selected_id();
+            if action == Action::CycleSort {
+                app.sort.key = app.sort.key.next();
+            } else {
+                app.sort.descending = !app.sort.descending;
+            }
+            app.reselect(prev);
+            app.status_line = format!("Sort: {}", app.sort);
+        }
+
+        Action::Undo => undo(app),
+        Action::Redo => redo(app),
 
         // Save marker
-        KeyCode::Char('s') => { app.status_line = "Saved ✓".into(); app.dirty = true; }
+        Action::Save => { app.status_line = "Saved ✓".into(); app.dirty = true; }
 
         // Map view toggle (World <-> NYC)
-        KeyCode::Char('m') => {
+        Action::ToggleMapView => {
             app.map_view = match app.map_view {
                 MapView::World => MapView::NYC,
                 MapView::NYC => MapView::World,
@@ -69,68 +319,330 @@ fn handle_normal_mode(app: &mut App, code: KeyCode) -> bool {
         }
 
         // tabs + visuals
-        KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => app.tabs.next(),
-        KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => app.tabs.prev(),
-        KeyCode::Char('t') => { app.show_chart = !app.show_chart; app.status_line = format!("Chart: {}", if app.show_chart { "On" } else { "Off" }); }
-        KeyCode::Char('g') => { app.enhanced_graphics = !app.enhanced_graphics; app.status_line = format!("Graphics: {}", if app.enhanced_graphics { "Enhanced" } else { "Normal" }); }
+        Action::NextTab => app.tabs.next(),
+        Action::PrevTab => app.tabs.prev(),
+        Action::ToggleChart => { app.show_chart = !app.show_chart; app.status_line = format!("Chart: {}", if app.show_chart { "On" } else { "Off" }); }
+        Action::ToggleGraphics => { app.enhanced_graphics = !app.enhanced_graphics; app.status_line = format!("Graphics: {}", if app.enhanced_graphics { "Enhanced" } else { "Normal" }); }
 
         _ => {}
     }
     true
 }
 
-fn handle_insert_mode(app: &mut App, code: KeyCode) -> bool {
-    match (app.insert_field, code) {
-        // Global controls
-        (_, KeyCode::Esc) => {
+/// Toggle done on the selected task, unless its blockers are still pending
+pub(super) fn toggle_selected(app: &mut App) {
+    if let Some(real_idx) = app.selected_index() {
+        let before = app.list.clone();
+        if app.list.toggle_done_index(real_idx) {
+            app.status_line = "Toggled ✓".into();
+            app.record(format!("Toggle \"{}\"", app.list.items[real_idx].title), before);
+        } else {
+            app.status_line = blocked_status(app, real_idx);
+        }
+    }
+}
+
+/// Form keys come from `keymap::INSERT_KEYS`; the rest go to the focused text field
+fn handle_insert_mode(app: &mut App, key: KeyEvent) -> bool {
+    let Some(action) = InsertAction::for_key(key.into(), app.insert_field) else {
+        if let Some(input) = app.active_input() {
+            input.handle_key(key);
+        }
+        return true;
+    };
+    match action {
+        InsertAction::Cancel => {
+            app.editing = None;
             app.input_mode = InputMode::Normal;
             app.status_line = "Cancelled".into();
         }
-        (_, KeyCode::Enter) => {
-            let title = app.draft_title.trim();
-            let notes = app.draft_notes.trim();
-            let tf = app.draft_timeframe.trim();
-            if !title.is_empty() {
-                let notes_opt = (!notes.is_empty()).then(|| notes.to_string());
-                let tf_opt = (!tf.is_empty()).then(|| tf.to_string());
-                app.list.add(title, app.draft_priority, notes_opt); // push to bottom
-                if let Some(last) = app.list.items.last_mut() {
-                    last.timeframe = tf_opt;
+        InsertAction::Submit => {
+            let parsed = parse_inline_tokens(app.draft_title.value());
+            let title = parsed.title.as_str();
+            let notes = app.draft_notes.value().trim();
+            let tf = app.draft_timeframe.value().trim();
+            let repeat = app.draft_recurrence.value().trim();
+            let recurrence = if repeat.is_empty() { Ok(None) } else { repeat.parse::<Recurrence>().map(Some) };
+            match recurrence {
+                _ if title.is_empty() => app.status_line = "Title cannot be empty".into(),
+                Err(e) => {
+                    app.status_line = e;
+                    app.insert_field = InsertField::Repeat;
+                }
+                Ok(recurrence) => {
+                    let notes_opt = (!notes.is_empty()).then(|| notes.to_string());
+                    let tf_opt = (!tf.is_empty()).then(|| tf.to_string());
+                    let before = app.list.clone();
+                    let editing = app.editing.take().and_then(|id| app.list.find_index_by_id(&id));
+                    let label = match editing {
+                        Some(idx) => format!("Edit \"{}\"", app.list.items[idx].title),
+                        None => format!("Add \"{title}\""),
+                    };
+                    if let Some(idx) = editing {
+                        // keeps id, created_at, status and links
+                        let task = &mut app.list.items[idx];
+                        task.title = title.to_string();
+                        task.notes = notes_opt;
+                        task.priority = app.draft_priority.clamp(1, 5);
+                        task.tags = parsed.tags;
+                        task.project = parsed.project;
+                        if task.timeframe != tf_opt {
+                            task.set_timeframe(tf_opt);
+                        }
+                        task.recurrence = recurrence;
+                        task.updated_at = Some(unix_now());
+                        app.status_line = "Updated ✓".to_string();
+                    } else {
+                        let task = app.list.add(title, app.draft_priority, notes_opt); // push to bottom
+                        task.parent_id = app.insert_parent.take();
+                        task.tags = parsed.tags;
+                        task.project = parsed.project;
+                        task.set_timeframe(tf_opt);
+                        task.recurrence = recurrence;
+                        app.status_line = "Added ✓".to_string();
+                    }
+                    app.record(label, before);
+                    app.input_mode = InputMode::Normal;
                 }
-                app.status_line = "Added ✓".to_string();
-                app.dirty = true;
-                app.input_mode = InputMode::Normal;
-            } else {
-                app.status_line = "Title cannot be empty".into();
             }
         }
 
-        // Field navigation (forward only)
-        (InsertField::Title, KeyCode::Tab) => app.insert_field = InsertField::Notes,
-        (InsertField::Notes, KeyCode::Tab) => app.insert_field = InsertField::Time,
-        (InsertField::Time, KeyCode::Tab)  => app.insert_field = InsertField::Priority,
-        (InsertField::Priority, KeyCode::Tab) => app.insert_field = InsertField::Title,
+        // Field navigation: Tab forward, Shift-Tab back
+        InsertAction::NextField => app.insert_field = app.insert_field.next(),
+        InsertAction::PrevField => app.insert_field = app.insert_field.prev(),
+        InsertAction::Help => help::open(app),
 
-        // Text editing for Title / Notes / Time
-        (InsertField::Title, KeyCode::Backspace) => { app.draft_title.pop(); }
-        (InsertField::Notes, KeyCode::Backspace) => { app.draft_notes.pop(); }
-        (InsertField::Time,  KeyCode::Backspace) => { app.draft_timeframe.pop(); }
+        // Priority editing with arrows, on the Priority field only (digits are ignored here)
+        InsertAction::RaisePriority => app.draft_priority = (app.draft_priority - 1).clamp(1, 5),
+        InsertAction::LowerPriority => app.draft_priority = (app.draft_priority + 1).clamp(1, 5),
+    }
+    true
+}
 
-        (InsertField::Title, KeyCode::Char(c)) => { app.draft_title.push(c); }
-        (InsertField::Notes, KeyCode::Char(c)) => { app.draft_notes.push(c); }
-        (InsertField::Time,  KeyCode::Char(c)) => { app.draft_timeframe.push(c); }
+fn blocked_status(app: &App, idx: usize) -> String {
+    let names: Vec<&str> = app
+        .list
+        .pending_blockers(idx)
+        .into_iter()
+        .map(|b| app.list.items[b].title.as_str())
+        .collect();
+    format!("🔒 Blocked by: {}", names.join(", "))
+}
 
-        // Priority editing with arrows (digits are ignored here)
-        (InsertField::Priority, KeyCode::Left)  |
-        (InsertField::Priority, KeyCode::Up)    => {
-            app.draft_priority = (app.draft_priority - 1).clamp(1, 5);
+/// Prompt keys come from `keymap::CONFIRM_DELETE_KEYS`, and likewise below
+fn handle_confirm_delete(app: &mut App, key: KeyEvent) -> bool {
+    let Some(idx) = app.pending_delete.as_deref().and_then(|id| app.list.find_index_by_id(id)) else {
+        app.pending_delete = None;
+        app.input_mode = InputMode::Normal;
+        return true;
+    };
+    let before = app.list.clone();
+    let title = app.list.items[idx].title.clone();
+    match PromptAction::for_key(app.input_mode, key.into()) {
+        Some(PromptAction::Help) => {
+            help::open(app);
+            return true;
         }
-        (InsertField::Priority, KeyCode::Right) |
-        (InsertField::Priority, KeyCode::Down)  => {
-            app.draft_priority = (app.draft_priority + 1).clamp(1, 5);
+        Some(PromptAction::DeleteTree) => {
+            let n = app.list.delete_tree(idx);
+            app.status_line = format!("Moved {n} tasks to trash ✓ (u to undo)");
+            app.record(format!("Delete \"{title}\" and subtasks"), before);
         }
+        Some(PromptAction::Reparent) => {
+            app.list.delete_index(idx);
+            app.status_line = "Moved to trash ✓ (subtasks moved up)".into();
+            app.record(format!("Delete \"{title}\""), before);
+        }
+        Some(PromptAction::Cancel) => app.status_line = "Cancelled".into(),
+        _ => return true,
+    }
+    app.clamp_selection();
+    app.pending_delete = None;
+    app.input_mode = InputMode::Normal;
+    true
+}
 
+fn handle_confirm_bulk_delete(app: &mut App, key: KeyEvent) -> bool {
+    match PromptAction::for_key(app.input_mode, key.into()) {
+        Some(PromptAction::Accept) => {
+            app.input_mode = InputMode::Normal;
+            bulk::delete(app);
+        }
+        Some(PromptAction::Cancel) => {
+            app.input_mode = InputMode::Normal;
+            app.status_line = "Cancelled".into();
+        }
+        Some(PromptAction::Help) => help::open(app),
         _ => {}
     }
     true
 }
+
+fn handle_pick_status(app: &mut App, key: KeyEvent) -> bool {
+    let pick = match PromptAction::for_key(app.input_mode, key.into()) {
+        Some(PromptAction::Up) => {
+            app.status_pick = app.status_pick.saturating_sub(1);
+            None
+        }
+        Some(PromptAction::Down) => {
+            app.status_pick = (app.status_pick + 1).min(Status::ALL.len() - 1);
+            None
+        }
+        Some(PromptAction::Accept) => Some(app.status_pick),
+        Some(PromptAction::Pick) => match key.code {
+            KeyCode::Char(c) => c.to_digit(10).map(|d| d as usize - 1),
+            _ => None,
+        },
+        Some(PromptAction::Cancel) => {
+            app.input_mode = InputMode::Normal;
+            app.status_line = "Cancelled".into();
+            None
+        }
+        Some(PromptAction::Help) => {
+            help::open(app);
+            None
+        }
+        _ => None,
+    };
+    let (Some(pick), Some(real_idx)) = (pick, app.selected_index()) else { return true };
+    let status = Status::ALL[pick];
+    let before = app.list.clone();
+    match app.list.set_status_index(real_idx, status) {
+        Ok(()) => {
+            app.status_line = format!("Status: {status} ✓");
+            app.record(format!("Set \"{}\" to {status}", app.list.items[real_idx].title), before);
+            app.input_mode = InputMode::Normal;
+        }
+        Err(e) => app.status_line = format!("Cannot change status: {e}"),
+    }
+    true
+}
+
+fn handle_search(app: &mut App, key: KeyEvent) -> bool {
+    let prev = app.selected_id();
+    match PromptAction::for_key(app.input_mode, key.into()) {
+        Some(PromptAction::Accept) => {
+            app.input_mode = InputMode::Normal;
+            app.status_line = format!("{} · {} match(es)", filter_status(app), app.visible_indices().len());
+            return true;
+        }
+        Some(PromptAction::Cancel) => {
+            app.filter.query.clear();
+            app.input_mode = InputMode::Normal;
+            app.status_line = "Search cleared".into();
+        }
+        Some(PromptAction::Up) => {
+            app.select_prev();
+            return true;
+        }
+        Some(PromptAction::Down) => {
+            app.select_next();
+            return true;
+        }
+        Some(PromptAction::Backspace) => {
+            app.filter.query.pop();
+        }
+        Some(PromptAction::Help) => {
+            help::open(app);
+            return true;
+        }
+        Some(_) => return true,
+        None => match typed(key) {
+            Some(c) => app.filter.query.push(c),
+            None => return true,
+        },
+    }
+    app.keep_selection(prev);
+    true
+}
+
+/// Move the selected task among its siblings. Switches to manual sort first,
+/// taking the order currently on screen as the starting point.
+fn move_selected(app: &mut App, delta: isize) {
+    let Some(real_idx) = app.selected_index() else { return };
+    app.ensure_manual_sort();
+    let before = app.list.clone();
+    let title = app.list.items[real_idx].title.clone();
+    if app.list.move_by(real_idx, delta) {
+        app.status_line = format!("Moved \"{title}\" {}", if delta < 0 { "up" } else { "down" });
+        app.record(format!("Move \"{title}\""), before);
+    } else {
+        app.status_line = "Can't move further".into();
+    }
+}
+
+fn handle_bulk_tag(app: &mut App, key: KeyEvent) -> bool {
+    match PromptAction::for_key(app.input_mode, key.into()) {
+        Some(PromptAction::Accept) => {
+            app.input_mode = InputMode::Normal;
+            let input = std::mem::take(&mut app.draft_tag);
+            bulk::tag(app, &input);
+        }
+        Some(PromptAction::Cancel) => {
+            app.input_mode = InputMode::Normal;
+            app.status_line = "Cancelled".into();
+        }
+        Some(PromptAction::Backspace) => {
+            app.draft_tag.pop();
+        }
+        Some(PromptAction::Help) => help::open(app),
+        Some(_) => {}
+        None => app.draft_tag.extend(typed(key)),
+    }
+    true
+}
+
+/// The character a key types into a prompt, if any
+fn typed(key: KeyEvent) -> Option<char> {
+    match key.code {
+        KeyCode::Char(c) => Some(c),
+        _ => None,
+    }
+}
+
+fn marked_status(app: &App) -> String {
+    let n = app.marked_ids().len();
+    let range = if app.mark_anchor.is_some() { " (range: move, V to finish)" } else { "" };
+    format!("{n} marked{range} · Enter toggle · d delete · </> priority · T tag · J/K move · Esc clear")
+}
+
+fn undo(app: &mut App) {
+    let prev = app.selected_id();
+    app.status_line = match app.history.undo(&mut app.list) {
+        Some(label) => {
+            app.dirty = true;
+            app.reselect(prev);
+            format!("Undid: {label}")
+        }
+        None => "Nothing to undo".into(),
+    };
+}
+
+fn redo(app: &mut App) {
+    let prev = app.selected_id();
+    app.status_line = match app.history.redo(&mut app.list) {
+        Some(label) => {
+            app.dirty = true;
+            app.reselect(prev);
+            format!("Redid: {label}")
+        }
+        None => "Nothing to redo".into(),
+    };
+}
+
+/// Next value after `current` in `options`, wrapping back to `None` after the last
+fn cycle(options: &[String], current: Option<&String>) -> Option<String> {
+    let next = match current {
+        None => 0,
+        Some(cur) => options.iter().position(|o| o.eq_ignore_ascii_case(cur)).map_or(0, |i| i + 1),
+    };
+    options.get(next).cloned()
+}
+
+fn filter_status(app: &App) -> String {
+    if app.filter.is_empty() {
+        "Filter: none".into()
+    } else {
+        format!("Filter: {}", app.filter.describe())
+    }
+}
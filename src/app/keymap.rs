@@ -0,0 +1,641 @@
+//! Normal-mode key bindings: an `Action` per command, a default table, and
+//! user overrides read from `keys.toml` in the config directory:
+//!
+//! ```toml
+//! [bindings]
+//! quit = ["q", "ctrl-c"]
+//! select_first = "g g"   # a chord: g, then g
+//! toggle_graphics = []   # unbound
+//! ```
+
+use std::{collections::BTreeMap, fmt, fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::app::{InputMode, InsertField, Tab};
+
+macro_rules! actions {
+    ($($variant:ident = $name:literal, $label:literal, $group:ident;)+) => {
+        /// Everything a key can be bound to in normal mode
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum Action {
+            $($variant,)+
+        }
+
+        impl Action {
+            pub const ALL: &[Action] = &[$(Action::$variant,)+];
+
+            /// Name used in `keys.toml`
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Action::$variant => $name,)+
+                }
+            }
+
+            /// Short description for help text
+            pub fn label(self) -> &'static str {
+                match self {
+                    $(Action::$variant => $label,)+
+                }
+            }
+
+            /// Where the `?` overlay lists it
+            pub fn group(self) -> Group {
+                match self {
+                    $(Action::$variant => Group::$group,)+
+                }
+            }
+        }
+    };
+}
+
+actions! {
+    Quit = "quit", "quit", General;
+    Help = "help", "help", General;
+    SelectNext = "select_next", "down", Navigation;
+    SelectPrev = "select_prev", "up", Navigation;
+    SelectFirst = "select_first", "first", Navigation;
+    SelectLast = "select_last", "last", Navigation;
+    NextTab = "next_tab", "next tab", Navigation;
+    PrevTab = "prev_tab", "prev tab", Navigation;
+    Add = "add", "add", Tasks;
+    AddSubtask = "add_subtask", "add subtask", Tasks;
+    Edit = "edit", "edit", Tasks;
+    EditNotes = "edit_notes", "notes in $EDITOR", Tasks;
+    ToggleDone = "toggle_done", "toggle done", Tasks;
+    ToggleTree = "toggle_tree", "toggle tree", Tasks;
+    PickStatus = "pick_status", "status", Tasks;
+    Delete = "delete", "delete", Tasks;
+    LinkBlocker = "link_blocker", "link blocker", Tasks;
+    MoveUp = "move_up", "move up", Order;
+    MoveDown = "move_down", "move down", Order;
+    Undo = "undo", "undo", General;
+    Redo = "redo", "redo", General;
+    Save = "save", "save", General;
+    Mark = "mark", "mark", Marks;
+    MarkRange = "mark_range", "mark range", Marks;
+    Cancel = "cancel", "clear marks / cancel link", Marks;
+    RaisePriority = "raise_priority", "raise priority (marked)", Marks;
+    LowerPriority = "lower_priority", "lower priority (marked)", Marks;
+    TagMarked = "tag_marked", "tag marked", Marks;
+    ToggleExpand = "toggle_expand", "expand/collapse", Details;
+    ScrollNotesDown = "scroll_notes_down", "scroll notes down", Details;
+    ScrollNotesUp = "scroll_notes_up", "scroll notes up", Details;
+    Fold = "fold", "fold", Details;
+    Search = "search", "search", Filters;
+    FilterTag = "filter_tag", "tag filter", Filters;
+    FilterProject = "filter_project", "project filter", Filters;
+    FilterStatus = "filter_status", "pending/done", Filters;
+    MaxPriority1 = "max_priority_1", "P1 only", Filters;
+    MaxPriority2 = "max_priority_2", "P1-2", Filters;
+    MaxPriority3 = "max_priority_3", "P1-3", Filters;
+    MaxPriority4 = "max_priority_4", "P1-4", Filters;
+    MaxPriority5 = "max_priority_5", "P1-5", Filters;
+    ClearFilters = "clear_filters", "clear filters", Filters;
+    CycleSort = "cycle_sort", "sort", Order;
+    FlipSort = "flip_sort", "flip sort", Order;
+    ToggleChart = "toggle_chart", "chart", Display;
+    ToggleGraphics = "toggle_graphics", "graphics", Display;
+    ToggleMapView = "toggle_map_view", "map view", Display;
+    Restore = "restore", "restore (trash)", Trash;
+    EmptyTrash = "empty_trash", "empty trash", Trash;
+}
+
+/// Headings of the `?` overlay, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Group {
+    General,
+    Navigation,
+    Tasks,
+    Details,
+    Marks,
+    Order,
+    Filters,
+    Display,
+    Trash,
+}
+
+impl Group {
+    pub const ALL: [Group; 9] = [
+        Group::General,
+        Group::Navigation,
+        Group::Tasks,
+        Group::Details,
+        Group::Marks,
+        Group::Order,
+        Group::Filters,
+        Group::Display,
+        Group::Trash,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Group::General => "General",
+            Group::Navigation => "Navigation",
+            Group::Tasks => "Tasks",
+            Group::Details => "Details & subtasks",
+            Group::Marks => "Marks & bulk edits",
+            Group::Order => "Order & sort",
+            Group::Filters => "Search & filters",
+            Group::Display => "Display",
+            Group::Trash => "Trash",
+        }
+    }
+
+    /// Whether the group's actions mean anything on `tab`
+    pub fn shown_on(self, tab: Tab) -> bool {
+        match self {
+            Group::General | Group::Navigation => true,
+            Group::Tasks | Group::Details | Group::Marks | Group::Order | Group::Filters => tab == Tab::Todos,
+            Group::Display => matches!(tab, Tab::Dash | Tab::World),
+            Group::Trash => tab == Tab::Trash,
+        }
+    }
+}
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        Self::ALL.iter().copied().find(|a| a.name() == name)
+    }
+
+    /// `MaxPriority1..5` as the priority they keep
+    pub fn max_priority(self) -> Option<i8> {
+        let n = match self {
+            Action::MaxPriority1 => 1,
+            Action::MaxPriority2 => 2,
+            Action::MaxPriority3 => 3,
+            Action::MaxPriority4 => 4,
+            Action::MaxPriority5 => 5,
+            _ => return None,
+        };
+        Some(n)
+    }
+}
+
+/// Built-in bindings, in the syntax of `keys.toml`
+const DEFAULTS: &[(Action, &[&str])] = &[
+    (Action::Quit, &["q"]),
+    (Action::Help, &["?"]),
+    (Action::SelectNext, &["down"]),
+    (Action::SelectPrev, &["up"]),
+    (Action::SelectFirst, &["home"]),
+    (Action::SelectLast, &["end", "G"]),
+    (Action::NextTab, &["tab", "right", "l"]),
+    (Action::PrevTab, &["backtab", "left", "h"]),
+    (Action::Add, &["a"]),
+    (Action::AddSubtask, &["A"]),
+    (Action::Edit, &["e"]),
+    (Action::EditNotes, &["E"]),
+    (Action::ToggleDone, &["enter"]),
+    (Action::ToggleTree, &["x"]),
+    (Action::PickStatus, &["p"]),
+    (Action::Delete, &["d"]),
+    (Action::LinkBlocker, &["b"]),
+    (Action::MoveUp, &["K", "shift-up"]),
+    (Action::MoveDown, &["J", "shift-down"]),
+    (Action::Undo, &["u"]),
+    (Action::Redo, &["ctrl-r"]),
+    (Action::Save, &["s"]),
+    (Action::Mark, &["v"]),
+    (Action::MarkRange, &["V"]),
+    (Action::Cancel, &["esc"]),
+    (Action::RaisePriority, &["<"]),
+    (Action::LowerPriority, &[">"]),
+    (Action::TagMarked, &["T"]),
+    (Action::ToggleExpand, &["space"]),
+    (Action::ScrollNotesDown, &["pagedown"]),
+    (Action::ScrollNotesUp, &["pageup"]),
+    (Action::Fold, &["c"]),
+    (Action::Search, &["/"]),
+    (Action::FilterTag, &["+"]),
+    (Action::FilterProject, &["@"]),
+    (Action::FilterStatus, &["f"]),
+    (Action::MaxPriority1, &["1"]),
+    (Action::MaxPriority2, &["2"]),
+    (Action::MaxPriority3, &["3"]),
+    (Action::MaxPriority4, &["4"]),
+    (Action::MaxPriority5, &["5"]),
+    (Action::ClearFilters, &["0"]),
+    (Action::CycleSort, &["o"]),
+    (Action::FlipSort, &["O"]),
+    (Action::ToggleChart, &["t"]),
+    (Action::ToggleGraphics, &["g"]),
+    (Action::ToggleMapView, &["m"]),
+    (Action::Restore, &["r"]),
+    (Action::EmptyTrash, &["X"]),
+];
+
+/// What a key does in the add/edit form; other keys go to the focused text field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertAction {
+    Cancel,
+    Submit,
+    NextField,
+    PrevField,
+    Help,
+    /// Only on the Priority field
+    RaisePriority,
+    LowerPriority,
+}
+
+/// Insert-mode keys, as `handle_insert_mode` reads them, with their help text
+pub const INSERT_KEYS: &[(InsertAction, &[&str], &str)] = &[
+    (InsertAction::Submit, &["enter"], "add / save"),
+    (InsertAction::Cancel, &["esc"], "cancel"),
+    (InsertAction::NextField, &["tab"], "next field"),
+    (InsertAction::PrevField, &["backtab"], "previous field"),
+    (InsertAction::Help, &["f1"], "help"),
+    (InsertAction::RaisePriority, &["left", "up"], "raise priority"),
+    (InsertAction::LowerPriority, &["right", "down"], "lower priority"),
+];
+
+impl InsertAction {
+    /// The action bound to `key` on the focused `field`
+    pub fn for_key(key: Key, field: InsertField) -> Option<InsertAction> {
+        INSERT_KEYS
+            .iter()
+            .find(|(_, keys, _)| keys.iter().any(|k| k.parse() == Ok(key)))
+            .map(|&(action, _, _)| action)
+            .filter(|a| field == InsertField::Priority || !a.priority_only())
+    }
+
+    pub fn priority_only(self) -> bool {
+        matches!(self, InsertAction::RaisePriority | InsertAction::LowerPriority)
+    }
+}
+
+/// What a key does in the smaller prompts; in search and bulk tagging, other
+/// keys are typed into the query
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptAction {
+    Accept,
+    Cancel,
+    Up,
+    Down,
+    Backspace,
+    Help,
+    /// Status picker: the status with that number
+    Pick,
+    /// Delete confirmation: trash the subtasks too
+    DeleteTree,
+    /// Delete confirmation: move the subtasks up a level
+    Reparent,
+}
+
+type PromptKeys = [(PromptAction, &'static [&'static str], &'static str)];
+
+pub const SEARCH_KEYS: &PromptKeys = &[
+    (PromptAction::Accept, &["enter"], "keep the filter"),
+    (PromptAction::Cancel, &["esc"], "clear the search"),
+    (PromptAction::Up, &["up"], "previous match"),
+    (PromptAction::Down, &["down"], "next match"),
+    (PromptAction::Backspace, &["backspace"], "delete a character"),
+    (PromptAction::Help, &["f1"], "help"),
+];
+
+pub const PICK_STATUS_KEYS: &PromptKeys = &[
+    (PromptAction::Up, &["up"], "previous status"),
+    (PromptAction::Down, &["down"], "next status"),
+    (PromptAction::Accept, &["enter"], "set status"),
+    (PromptAction::Pick, &["1", "2", "3", "4", "5", "6"], "set status by number"),
+    (PromptAction::Cancel, &["esc"], "cancel"),
+    (PromptAction::Help, &["?", "f1"], "help"),
+];
+
+pub const CONFIRM_DELETE_KEYS: &PromptKeys = &[
+    (PromptAction::DeleteTree, &["a", "y"], "delete with subtasks"),
+    (PromptAction::Reparent, &["r"], "delete, keep subtasks"),
+    (PromptAction::Cancel, &["esc", "n"], "cancel"),
+    (PromptAction::Help, &["?", "f1"], "help"),
+];
+
+pub const CONFIRM_BULK_DELETE_KEYS: &PromptKeys = &[
+    (PromptAction::Accept, &["y", "enter"], "delete the marked tasks"),
+    (PromptAction::Cancel, &["n", "esc"], "cancel"),
+    (PromptAction::Help, &["?", "f1"], "help"),
+];
+
+pub const BULK_TAG_KEYS: &PromptKeys = &[
+    (PromptAction::Accept, &["enter"], "apply +tag / -tag"),
+    (PromptAction::Cancel, &["esc"], "cancel"),
+    (PromptAction::Backspace, &["backspace"], "delete a character"),
+    (PromptAction::Help, &["f1"], "help"),
+];
+
+impl PromptAction {
+    /// Keys of the prompt open in `mode`, with its name for the help overlay
+    pub fn table(mode: InputMode) -> Option<(&'static str, &'static PromptKeys)> {
+        Some(match mode {
+            InputMode::Search => ("Search", SEARCH_KEYS),
+            InputMode::PickStatus => ("Status picker", PICK_STATUS_KEYS),
+            InputMode::ConfirmDelete => ("Delete with subtasks", CONFIRM_DELETE_KEYS),
+            InputMode::ConfirmBulkDelete => ("Delete marked tasks", CONFIRM_BULK_DELETE_KEYS),
+            InputMode::BulkTag => ("Tag marked tasks", BULK_TAG_KEYS),
+            _ => return None,
+        })
+    }
+
+    /// The action bound to `key` in the prompt open in `mode`
+    pub fn for_key(mode: InputMode, key: Key) -> Option<PromptAction> {
+        Self::table(mode)?
+            .1
+            .iter()
+            .find(|(_, keys, _)| keys.iter().any(|k| k.parse() == Ok(key)))
+            .map(|&(action, _, _)| action)
+    }
+}
+
+/// `keys` from a table as the help shows them, e.g. "← / ↑"
+pub fn format_keys(keys: &[&str]) -> String {
+    keys.iter().filter_map(|k| parse_sequence(k).ok()).map(|s| format_sequence(&s)).collect::<Vec<_>>().join("/")
+}
+
+/// One key press with the modifiers that matter for binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl From<KeyEvent> for Key {
+    fn from(ev: KeyEvent) -> Self {
+        let mut modifiers = ev.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        // Shift is already part of `K` or BackTab
+        if matches!(ev.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Key { code: ev.code, modifiers }
+    }
+}
+
+impl std::str::FromStr for Key {
+    type Err = String;
+
+    /// `a`, `K`, `ctrl-r`, `alt-enter`, `shift-up`, `space`, `f5`, …
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        while let Some((m, tail)) = rest.split_once('-').filter(|(_, tail)| !tail.is_empty()) {
+            modifiers |= match m.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{m}' in '{s}'")),
+            };
+            rest = tail;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "enter" | "return" => KeyCode::Enter,
+                "esc" | "escape" => KeyCode::Esc,
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                f if f.starts_with('f') && f[1..].parse::<u8>().is_ok() => KeyCode::F(f[1..].parse().unwrap_or(1)),
+                _ => return Err(format!("unknown key '{rest}' in '{s}'")),
+            },
+        };
+        let mut key = Key { code, modifiers };
+        // `shift-tab` is what terminals send as BackTab; `shift-k` is `K`
+        if modifiers.contains(KeyModifiers::SHIFT) {
+            match key.code {
+                KeyCode::Tab => key.code = KeyCode::BackTab,
+                KeyCode::Char(c) => key.code = KeyCode::Char(c.to_ascii_uppercase()),
+                _ => {}
+            }
+            if matches!(key.code, KeyCode::BackTab | KeyCode::Char(_)) {
+                key.modifiers.remove(KeyModifiers::SHIFT);
+            }
+        }
+        Ok(key)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("^")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("M-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            f.write_str("⇧")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::BackTab => f.write_str("⇧Tab"),
+            KeyCode::Backspace => f.write_str("Bksp"),
+            KeyCode::Delete => f.write_str("Del"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// A key or a chord of keys pressed one after another
+pub type Sequence = Vec<Key>;
+
+fn parse_sequence(s: &str) -> Result<Sequence, String> {
+    let keys: Result<Sequence, String> = s.split_whitespace().map(str::parse).collect();
+    keys.and_then(|k| if k.is_empty() { Err("empty key binding".into()) } else { Ok(k) })
+}
+
+pub fn format_sequence(seq: &[Key]) -> String {
+    seq.iter().map(Key::to_string).collect::<Vec<_>>().join(" ")
+}
+
+/// What a key press amounted to
+#[derive(Debug, PartialEq, Eq)]
+pub enum Resolved {
+    Action(Action),
+    /// The keys so far start a chord; wait for the next one
+    Pending,
+    Unbound,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Earlier entries win when two bindings collide
+    bindings: Vec<(Sequence, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::with_overrides(&BTreeMap::new()).expect("default bindings parse")
+    }
+}
+
+/// `keys.toml`
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFile {
+    #[serde(default)]
+    bindings: BTreeMap<String, OneOrMany>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Keymap {
+    /// Default bindings with the user's `keys.toml` applied, if it exists
+    pub fn load(path: &Path) -> Result<Keymap> {
+        if !path.exists() {
+            return Ok(Keymap::default());
+        }
+        let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Keymap::parse(&text).with_context(|| format!("in {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Keymap> {
+        let file: KeysFile = toml::from_str(text)?;
+        let mut overrides = BTreeMap::new();
+        for (name, keys) in file.bindings {
+            let Some(action) = Action::from_name(&name) else {
+                bail!("unknown action '{name}'; known actions: {}", Action::ALL.iter().map(|a| a.name()).collect::<Vec<_>>().join(", "));
+            };
+            let keys = match keys {
+                OneOrMany::One(k) => vec![k],
+                OneOrMany::Many(ks) => ks,
+            };
+            let seqs = keys.iter().map(|k| parse_sequence(k)).collect::<Result<Vec<_>, _>>();
+            overrides.insert(action, seqs.map_err(|e| anyhow::anyhow!("binding for '{name}': {e}"))?);
+        }
+        Keymap::with_overrides(&overrides).map_err(anyhow::Error::msg)
+    }
+
+    /// User bindings replace the defaults of their action and take precedence
+    fn with_overrides(overrides: &BTreeMap<Action, Vec<Sequence>>) -> Result<Keymap, String> {
+        let mut bindings: Vec<(Sequence, Action)> = Vec::new();
+        for (&action, seqs) in overrides {
+            bindings.extend(seqs.iter().map(|s| (s.clone(), action)));
+        }
+        for &(action, keys) in DEFAULTS {
+            if !overrides.contains_key(&action) {
+                for k in keys {
+                    bindings.push((parse_sequence(k)?, action));
+                }
+            }
+        }
+        Ok(Keymap { bindings })
+    }
+
+    /// Bindings that can never fire: the same keys bound twice, or a key that is
+    /// also the start of a chord (the shorter binding wins)
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        for (i, (seq, action)) in self.bindings.iter().enumerate() {
+            for (earlier, other) in &self.bindings[..i] {
+                let (short, long) = if earlier.len() <= seq.len() { (earlier, seq) } else { (seq, earlier) };
+                if other != action && long.starts_with(short) {
+                    out.push(format!(
+                        "'{}' ({}) conflicts with '{}' ({})",
+                        format_sequence(seq),
+                        action.name(),
+                        format_sequence(earlier),
+                        other.name()
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// Feed one key press; `pending` holds the chord typed so far
+    pub fn resolve(&self, pending: &mut Sequence, key: Key) -> Resolved {
+        pending.push(key);
+        if let Some(&(_, action)) = self.bindings.iter().find(|(s, _)| s == pending) {
+            pending.clear();
+            return Resolved::Action(action);
+        }
+        if self.bindings.iter().any(|(s, _)| s.starts_with(pending)) {
+            return Resolved::Pending;
+        }
+        // a broken chord: try the last key on its own
+        let retry = pending.len() > 1;
+        pending.clear();
+        if retry { self.resolve(pending, key) } else { Resolved::Unbound }
+    }
+
+    /// Keys bound to `action`, e.g. "K/⇧↑"
+    pub fn keys_for(&self, action: Action) -> String {
+        let keys: Vec<String> = self.bindings.iter().filter(|(_, a)| *a == action).map(|(s, _)| format_sequence(s)).collect();
+        keys.join("/")
+    }
+
+    /// "key label | key label …" for the given actions, skipping unbound ones
+    pub fn help_line(&self, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .filter_map(|&a| {
+                let keys = self.keys_for(a);
+                (!keys.is_empty()).then(|| format!("{keys} {}", a.label()))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(map: &Keymap, pending: &mut Sequence, key: &str) -> Resolved {
+        map.resolve(pending, key.parse().unwrap())
+    }
+
+    #[test]
+    fn overrides_chords_and_conflicts() {
+        let map = Keymap::parse("[bindings]\nselect_first = \"g g\"\nquit = [\"ctrl-q\", \"alt-x\"]\n").unwrap();
+        let mut pending = Vec::new();
+        // `g` alone still toggles graphics, so the chord is reported and shadowed
+        assert_eq!(press(&map, &mut pending, "g"), Resolved::Action(Action::ToggleGraphics));
+        assert_eq!(map.conflicts().len(), 1);
+        assert_eq!(press(&map, &mut pending, "q"), Resolved::Unbound);
+        assert_eq!(press(&map, &mut pending, "ctrl-q"), Resolved::Action(Action::Quit));
+        assert_eq!(map.keys_for(Action::Quit), "^q/M-x");
+
+        let map = Keymap::parse("[bindings]\nselect_first = \"g g\"\ntoggle_graphics = []\n").unwrap();
+        assert!(map.conflicts().is_empty());
+        assert_eq!(press(&map, &mut pending, "g"), Resolved::Pending);
+        assert_eq!(press(&map, &mut pending, "g"), Resolved::Action(Action::SelectFirst));
+        // a broken chord falls back to the last key
+        press(&map, &mut pending, "g");
+        assert_eq!(press(&map, &mut pending, "shift-k"), Resolved::Action(Action::MoveUp));
+
+        assert!(Keymap::parse("[bindings]\nfly = \"f\"").unwrap_err().to_string().contains("unknown action 'fly'"));
+        assert!(Keymap::parse("[bindingz]\n").is_err());
+        assert!(Keymap::default().conflicts().is_empty());
+    }
+}
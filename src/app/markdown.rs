@@ -0,0 +1,236 @@
+//! Minimal Markdown → ratatui text for task notes: headings, lists and
+//! checkboxes, emphasis, inline code, code blocks, quotes and links.
+
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::app::theme::Theme;
+
+/// Render `text` as styled lines; wrapping is left to the `Paragraph`
+pub fn render(text: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let options = Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    let mut r = Renderer {
+        theme,
+        lines: Vec::new(),
+        current: Vec::new(),
+        styles: Vec::new(),
+        lists: Vec::new(),
+        quote: 0,
+        in_code_block: false,
+        link: None,
+    };
+    for event in Parser::new_ext(text, options) {
+        r.event(event);
+    }
+    r.flush();
+    r.lines
+}
+
+struct Renderer<'t> {
+    theme: &'t Theme,
+    lines: Vec<Line<'static>>,
+    current: Vec<Span<'static>>,
+    styles: Vec<Style>,
+    /// One entry per open list: the next number, or None for bullets
+    lists: Vec<Option<u64>>,
+    quote: usize,
+    in_code_block: bool,
+    /// Link target and where its text starts in `current`
+    link: Option<(String, usize)>,
+}
+
+impl Renderer<'_> {
+    fn code(&self) -> Style {
+        self.theme.fg(self.theme.code)
+    }
+
+    fn dim(&self) -> Style {
+        self.theme.fg(self.theme.faint)
+    }
+
+    fn style(&self) -> Style {
+        self.styles.iter().fold(Style::default(), |acc, s| acc.patch(*s))
+    }
+
+    /// Prefix for a new line: quote bars, then indentation under list items
+    fn line_prefix(&mut self) {
+        if self.quote > 0 {
+            self.current.push(Span::styled("▎ ".repeat(self.quote), self.dim()));
+        }
+        if !self.lists.is_empty() {
+            self.current.push(Span::raw("  ".repeat(self.lists.len())));
+        }
+    }
+
+    fn push(&mut self, span: Span<'static>) {
+        if self.current.is_empty() {
+            self.line_prefix();
+        }
+        self.current.push(span);
+    }
+
+    fn flush(&mut self) {
+        if !self.current.is_empty() {
+            self.lines.push(Line::from(std::mem::take(&mut self.current)));
+        }
+    }
+
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.in_code_block => {
+                for line in text.lines() {
+                    self.push(Span::styled(format!("  {line}"), self.code()));
+                    self.flush();
+                }
+            }
+            Event::Text(text) => self.push(Span::styled(text.into_string(), self.style())),
+            Event::Code(code) => self.push(Span::styled(format!("`{code}`"), self.code())),
+            Event::TaskListMarker(checked) => {
+                // replace the bullet that `Item` just pushed
+                self.current.pop();
+                self.current.push(if checked {
+                    Span::styled("☑ ", self.theme.fg(self.theme.success))
+                } else {
+                    Span::raw("☐ ")
+                });
+            }
+            Event::SoftBreak => self.push(Span::raw(" ")),
+            Event::HardBreak => self.flush(),
+            Event::Rule => {
+                self.flush();
+                self.push(Span::styled("────────", self.dim()));
+                self.flush();
+            }
+            Event::Html(html) | Event::InlineHtml(html) => self.push(Span::styled(html.trim_end().to_string(), self.dim())),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush();
+                let style = match level {
+                    HeadingLevel::H1 => self.theme.fg(self.theme.accent).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    HeadingLevel::H2 => self.theme.fg(self.theme.accent).add_modifier(Modifier::BOLD),
+                    _ => self.theme.fg(self.theme.tag).add_modifier(Modifier::BOLD),
+                };
+                self.styles.push(style);
+            }
+            Tag::List(start) => {
+                self.flush();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush();
+                let depth = self.lists.len().saturating_sub(1);
+                let marker = match self.lists.last_mut() {
+                    Some(Some(n)) => {
+                        *n += 1;
+                        format!("{}. ", *n - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                if self.quote > 0 {
+                    self.current.push(Span::styled("▎ ".repeat(self.quote), self.dim()));
+                }
+                self.current.push(Span::raw("  ".repeat(depth)));
+                self.current.push(Span::styled(marker, self.theme.fg(self.theme.tag)));
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush();
+                self.in_code_block = true;
+                if let CodeBlockKind::Fenced(lang) = kind
+                    && !lang.is_empty()
+                {
+                    self.push(Span::styled(format!("  {lang}"), self.dim()));
+                    self.flush();
+                }
+            }
+            Tag::BlockQuote(_) => {
+                self.flush();
+                self.quote += 1;
+                self.styles.push(Style::new().add_modifier(Modifier::ITALIC));
+            }
+            Tag::Emphasis => self.styles.push(Style::new().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.styles.push(Style::new().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => self.styles.push(Style::new().add_modifier(Modifier::CROSSED_OUT)),
+            Tag::Link { dest_url, .. } => {
+                self.link = Some((dest_url.into_string(), self.current.len()));
+                self.styles.push(self.theme.fg(self.theme.project).add_modifier(Modifier::UNDERLINED));
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush();
+            }
+            TagEnd::Paragraph | TagEnd::Item => self.flush(),
+            TagEnd::List(_) => {
+                self.flush();
+                self.lists.pop();
+            }
+            TagEnd::CodeBlock => self.in_code_block = false,
+            TagEnd::BlockQuote(_) => {
+                self.flush();
+                self.quote -= 1;
+                self.styles.pop();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link => {
+                self.styles.pop();
+                if let Some((url, from)) = self.link.take() {
+                    let text: String = self.current.get(from..).unwrap_or_default().iter().map(|s| s.content.as_ref()).collect();
+                    // autolinks already show their target
+                    if text != url && !url.is_empty() {
+                        self.push(Span::styled(format!(" <{url}>"), self.dim()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect()).collect()
+    }
+
+    #[test]
+    fn renders_blocks_lists_and_links() {
+        let notes = "# Plan\nSee [docs](https://example.com) and `cargo test`.\n\n\
+                     - [ ] draft\n- [x] review\n  1. first\n  2. second\n\n```sh\nmake\n\n  run\n```\n> quoted";
+        assert_eq!(
+            plain(&render(notes, &Theme::default())),
+            [
+                "Plan",
+                "See docs <https://example.com> and `cargo test`.",
+                "☐ draft",
+                "☑ review",
+                "  1. first",
+                "  2. second",
+                "  sh",
+                "  make",
+                "  ",
+                "    run",
+                "▎ quoted",
+            ]
+        );
+        assert!(render("**bold**", &Theme::default())[0].spans[0].style.add_modifier.contains(Modifier::BOLD));
+    }
+}
@@ -1,13 +1,37 @@
 pub mod ui;
 pub mod input;
-
-use crate::task::Status;
-use crate::todolist::TodoList;
+mod bulk;
+pub mod textinput;
+mod markdown;
+pub mod help;
+pub mod keymap;
+pub mod mouse;
+pub mod trash;
+pub mod theme;
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    time::Instant,
+};
+
+use crate::config::Config;
+use crate::history::History;
+use help::Help;
+use keymap::{Keymap, Sequence};
+use mouse::HitAreas;
+use theme::Theme;
+use textinput::TextInput;
+use crate::task::{unix_now, Status};
+use crate::todolist::{SortKey, SortMode, TaskFilter, TodoList};
 
 // ---- NEW: path data type for NYC map (GeoJSON→paths conversion output) ----
 #[derive(Debug, serde::Deserialize, Clone)]
 pub struct MapPaths(pub Vec<Vec<Vec<[f64; 2]>>>); // features -> rings -> [lon,lat]
 
+/// ([min_lon, max_lon], [min_lat, max_lat])
+pub type MapBbox = ([f64; 2], [f64; 2]);
+
 // Minimal GeoJSON structs (only what we need)
 #[derive(Debug, serde::Deserialize)]
 struct GeoFeatureCollection {
@@ -27,29 +51,67 @@ struct GeoGeometry {
 }
 
 // Tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tab { Todos, Dash, World, Trash }
+
+impl Tab {
+    pub const ALL: [Tab; 4] = [Tab::Todos, Tab::Dash, Tab::World, Tab::Trash];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Tab::Todos => "Todos",
+            Tab::Dash => "Dash",
+            Tab::World => "World",
+            Tab::Trash => "Trash",
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Tabs {
-    pub titles: Vec<&'static str>,
+    pub tabs: Vec<Tab>,
     pub index: usize,
 }
 impl Tabs {
-    pub fn new(titles: Vec<&'static str>) -> Self {
-        Self { titles, index: 0 }
+    pub fn new(tabs: Vec<Tab>) -> Self {
+        Self { tabs, index: 0 }
+    }
+    pub fn current(&self) -> Tab {
+        self.tabs.get(self.index).copied().unwrap_or(Tab::Todos)
+    }
+    pub fn titles(&self) -> Vec<&'static str> {
+        self.tabs.iter().map(|t| t.title()).collect()
     }
     pub fn next(&mut self) {
-        self.index = (self.index + 1) % self.titles.len();
+        self.index = (self.index + 1) % self.tabs.len();
     }
     pub fn prev(&mut self) {
-        self.index = if self.index == 0 { self.titles.len() - 1 } else { self.index - 1 };
+        self.index = if self.index == 0 { self.tabs.len() - 1 } else { self.index - 1 };
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum InputMode { Normal, Insert }
+pub enum InputMode { Normal, Insert, ConfirmDelete, ConfirmBulkDelete, PickStatus, Search, BulkTag, Help }
 
-// Focusable fields in Insert mode (Tab cycles through these)
+// Focusable fields in Insert mode (Tab / Shift-Tab cycle through these)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum InsertField { Title, Notes, Time, Priority }
+pub enum InsertField { Title, Notes, Time, Repeat, Priority }
+
+impl InsertField {
+    const ORDER: [InsertField; 5] =
+        [InsertField::Title, InsertField::Notes, InsertField::Time, InsertField::Repeat, InsertField::Priority];
+
+    pub fn next(self) -> Self {
+        let i = Self::ORDER.iter().position(|&f| f == self).unwrap_or(0);
+        Self::ORDER[(i + 1) % Self::ORDER.len()]
+    }
+
+    pub fn prev(self) -> Self {
+        let i = Self::ORDER.iter().position(|&f| f == self).unwrap_or(0);
+        Self::ORDER[(i + Self::ORDER.len() - 1) % Self::ORDER.len()]
+    }
+}
 
 // Map view selector in the World tab
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -63,31 +125,83 @@ pub struct App {
 
     // insert-mode drafts
     pub insert_field: InsertField,
-    pub draft_title: String,
+    pub insert_parent: Option<String>, // id of the parent when adding a subtask
+    pub editing: Option<String>,       // id of the task being edited, None when adding
+    pub draft_title: TextInput,
     pub draft_priority: i8,
-    pub draft_notes: String,
-    pub draft_timeframe: String,
+    pub draft_notes: TextInput,
+    pub draft_timeframe: TextInput,
+    pub draft_recurrence: TextInput,
 
     pub status_line: String,
     pub dirty: bool,
 
+    // undo/redo snapshots, persisted next to the data file
+    pub history: History,
+
     // visuals/animation
     pub tabs: Tabs,
+    pub theme: Theme,
     pub show_chart: bool,
     pub enhanced_graphics: bool,
-    pub progress: f64, // 0..1 wave
-    pub pulse: f64,    // 0..tau loop
+    pub default_priority: i8,
+    // no decorative animation: exact values, and no redraws while idle
+    pub reduced_motion: bool,
+    pub pulse: f64, // 0..tau loop
     pub spark_points: Vec<u64>,
 
     // inline expansion in Todos tab
     pub expanded: bool,
+    pub notes_scroll: u16, // first notes line shown in the expanded panel
+    pub notes_scroll_max: Cell<u16>, // set while drawing, once the wrapped height is known
+
+    // tag / project / status / priority / search filter applied to the Todos table
+    pub filter: TaskFilter,
+
+    // order of the Todos table, restored from the state file on start
+    pub sort: SortMode,
+
+    // multi-select: ids marked with `v`, plus the start of a `V` range in progress
+    pub marked: HashSet<String>,
+    pub mark_anchor: Option<String>,
+    pub draft_tag: String,
+
+    // subtask tree: ids of collapsed parents, and whether progress rolls up through parents
+    pub collapsed: HashSet<String>,
+    pub rollup_progress: bool,
+
+    // id of the parent waiting for a delete-or-reparent answer
+    pub pending_delete: Option<String>,
+
+    // id of the blocker picked with `b`, waiting for the task it blocks
+    pub link_from: Option<String>,
+
+    // id of the task whose notes should open in $EDITOR; main owns the terminal so it runs it
+    pub notes_editor: Option<String>,
+
+    // highlighted row of the status picker (index into Status::ALL)
+    pub status_pick: usize,
 
     // world tab view
     pub map_view: MapView,
 
+    // highlighted row of the Trash tab (position in `trash::rows`)
+    pub trash_selected: usize,
+
+    // `?` overlay: search text, scroll position and the mode to return to
+    pub help: Help,
+
+    // normal-mode bindings and the chord typed so far
+    pub keymap: Keymap,
+    pub pending_keys: Sequence,
+
+    // mouse: clickable areas from the last draw, and the last row click (for double-clicks)
+    pub hits: RefCell<HitAreas>,
+    pub last_click: Option<(Instant, usize)>,
+
     // ---- NYC map data loaded once at startup (optional) ----
     pub nyc_paths: Option<MapPaths>,
-    pub nyc_bbox: Option<([f64; 2], [f64; 2])>, // ([min_lon, max_lon], [min_lat, max_lat])
+    pub nyc_bbox: Option<MapBbox>,
 }
 
 impl App {
@@ -100,38 +214,207 @@ impl App {
             selected: 0,
             input_mode: InputMode::Normal,
             insert_field: InsertField::Title,
-            draft_title: String::new(),
+            insert_parent: None,
+            editing: None,
+            draft_title: TextInput::default(),
             draft_priority: 1,
-            draft_notes: String::new(),
-            draft_timeframe: String::new(),
+            draft_notes: TextInput::default(),
+            draft_timeframe: TextInput::default(),
+            draft_recurrence: TextInput::default(),
             status_line: String::new(),
             dirty: false,
+            history: History::default(),
 
-            tabs: Tabs::new(vec!["Todos", "Dash", "World"]),
+            tabs: Tabs::new(Tab::ALL.to_vec()),
+            theme: Theme::default(),
             show_chart: true,
             enhanced_graphics: true,
+            default_priority: 1,
+            reduced_motion: false,
 
-            progress: 0.0,
             pulse: 0.0,
             spark_points: vec![0; 60],
 
             expanded: false,
+            notes_scroll: 0,
+            notes_scroll_max: Cell::new(0),
+            filter: TaskFilter::default(),
+            sort: SortMode::default(),
+            marked: HashSet::new(),
+            mark_anchor: None,
+            draft_tag: String::new(),
+            collapsed: HashSet::new(),
+            rollup_progress: true,
+            pending_delete: None,
+            link_from: None,
+            notes_editor: None,
+            status_pick: 0,
             map_view: MapView::World,
+            trash_selected: 0,
+            help: Help::default(),
+            keymap: Keymap::default(),
+            pending_keys: Vec::new(),
+            hits: RefCell::default(),
+            last_click: None,
 
             nyc_paths,
             nyc_bbox,
         }
     }
 
+    /// Text field focused in the add/edit overlay, if the focus is on one
+    pub fn active_input(&mut self) -> Option<&mut TextInput> {
+        match self.insert_field {
+            InsertField::Title => Some(&mut self.draft_title),
+            InsertField::Notes => Some(&mut self.draft_notes),
+            InsertField::Time => Some(&mut self.draft_timeframe),
+            InsertField::Repeat => Some(&mut self.draft_recurrence),
+            InsertField::Priority => None,
+        }
+    }
+
+    /// Mark the list dirty and remember `before` so the change can be undone
+    pub fn record(&mut self, label: impl Into<String>, before: TodoList) {
+        let prev = self.rows_of(&before).get(self.selected).map(|&(i, _)| before.items[i].id.clone());
+        self.history.record(label, before);
+        self.dirty = true;
+        self.reselect(prev);
+    }
+
+    /// Store the outcome of editing a task's notes in the external editor
+    pub fn finish_notes_edit(&mut self, id: &str, edited: anyhow::Result<Option<String>>) {
+        let Some(idx) = self.list.find_index_by_id(id) else { return };
+        match edited {
+            Err(e) => self.status_line = format!("Notes not saved: {e:#}"),
+            Ok(None) => self.status_line = "Notes unchanged".into(),
+            Ok(Some(notes)) => {
+                let before = self.list.clone();
+                let task = &mut self.list.items[idx];
+                task.notes = (!notes.is_empty()).then_some(notes);
+                task.updated_at = Some(unix_now());
+                let label = format!("Edit notes of \"{}\"", task.title);
+                self.status_line = "Notes saved ✓".into();
+                self.record(label, before);
+            }
+        }
+    }
+
+    /// Move the selection back onto the task `prev` after the list or its sort changed
+    pub fn reselect(&mut self, prev: Option<String>) {
+        if let Some(id) = prev {
+            self.select_id(&id);
+        }
+        self.clamp_selection();
+    }
+
     pub fn visible_indices(&self) -> Vec<usize> {
-        (0..self.list.items.len()).collect()
+        self.visible_rows().into_iter().map(|(i, _)| i).collect()
+    }
+
+    /// Visible tasks in tree order as `(index, depth)`, skipping collapsed subtrees
+    pub fn visible_rows(&self) -> Vec<(usize, usize)> {
+        self.rows_of(&self.list)
+    }
+
+    /// `visible_rows` for another version of the list, e.g. the one before an edit
+    fn rows_of(&self, list: &TodoList) -> Vec<(usize, usize)> {
+        let mut hidden_below: Option<usize> = None;
+        let mut rows = Vec::new();
+        for (i, depth) in list.tree_order_by(self.sort) {
+            match hidden_below {
+                Some(d) if depth > d => continue,
+                _ => hidden_below = None,
+            }
+            if self.collapsed.contains(&list.items[i].id) {
+                hidden_below = Some(depth);
+            }
+            if self.filter.matches(&list.items[i]) {
+                rows.push((i, depth));
+            }
+        }
+        rows
+    }
+
+    /// Real index of the selected task
+    pub fn selected_index(&self) -> Option<usize> {
+        self.visible_indices().get(self.selected).copied()
+    }
+
+    /// Switch to manual sort, taking the order on screen as the starting point
+    pub fn ensure_manual_sort(&mut self) {
+        if self.sort.key != SortKey::Manual {
+            self.list.adopt_order(self.sort);
+            self.sort = SortMode::default();
+        }
+    }
+
+    /// Ids of visible rows in the open `V` range
+    fn range_ids(&self) -> Vec<String> {
+        let Some(anchor) = &self.mark_anchor else { return Vec::new() };
+        let rows = self.visible_indices();
+        let Some(from) = rows.iter().position(|&i| &self.list.items[i].id == anchor) else { return Vec::new() };
+        let (lo, hi) = (from.min(self.selected), from.max(self.selected).min(rows.len().saturating_sub(1)));
+        rows[lo..=hi].iter().map(|&i| self.list.items[i].id.clone()).collect()
+    }
+
+    pub fn is_marked(&self, id: &str) -> bool {
+        self.marked.contains(id) || self.range_ids().iter().any(|r| r == id)
+    }
+
+    pub fn has_marks(&self) -> bool {
+        !self.marked.is_empty() || self.mark_anchor.is_some()
+    }
+
+    /// Marked task ids (including an open range) in table order; marks on
+    /// rows hidden by filters or folds still count
+    pub fn marked_ids(&self) -> Vec<String> {
+        let range = self.range_ids();
+        self.list
+            .tree_order_by(self.sort)
+            .into_iter()
+            .map(|(i, _)| &self.list.items[i].id)
+            .filter(|id| self.marked.contains(*id) || range.contains(id))
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.marked.clear();
+        self.mark_anchor = None;
+    }
+
+    pub fn selected_id(&self) -> Option<String> {
+        self.selected_index().map(|i| self.list.items[i].id.clone())
+    }
+
+    /// After the filter changed: stay on the task with `prev` if it is still
+    /// visible, otherwise jump to the first row
+    pub fn keep_selection(&mut self, prev: Option<String>) {
+        self.selected = 0;
+        if let Some(id) = prev {
+            self.select_id(&id);
+        }
+    }
+
+    /// Move the selection onto the task with `id`, if it is visible
+    pub fn select_id(&mut self, id: &str) {
+        if let Some(pos) = self.visible_indices().iter().position(|&i| self.list.items[i].id == id) {
+            self.selected = pos;
+        }
     }
     pub fn select_next(&mut self) {
         let len = self.visible_indices().len();
         if len > 0 && self.selected + 1 < len { self.selected += 1; }
+        self.notes_scroll = 0;
     }
     pub fn select_prev(&mut self) {
         if self.selected > 0 { self.selected -= 1; }
+        self.notes_scroll = 0;
+    }
+
+    /// Scroll the notes in the expanded panel, stopping once the last line is in view
+    pub fn scroll_notes(&mut self, delta: i16) {
+        self.notes_scroll = self.notes_scroll.saturating_add_signed(delta).min(self.notes_scroll_max.get());
     }
     pub fn clamp_selection(&mut self) {
         let len = self.visible_indices().len();
@@ -139,14 +422,20 @@ impl App {
         else if self.selected >= len { self.selected = len - 1; }
     }
 
-    // metrics
+    // metrics (cancelled tasks don't count towards completion)
     pub fn percent_done(&self) -> f64 {
-        let total = self.list.items.len() as f64;
+        if self.rollup_progress {
+            return self.list.rolled_up_progress();
+        }
+        let total = (self.list.items.len() - self.list.count_status(Status::Cancelled)) as f64;
         if total == 0.0 { 0.0 } else {
-            let done = self.list.items.iter().filter(|t| t.status == Status::Done).count() as f64;
+            let done = self.list.done_count() as f64;
             done / total
         }
     }
+    pub fn counts_by_status(&self) -> Vec<(Status, u64)> {
+        Status::ALL.iter().map(|&s| (s, self.list.count_status(s) as u64)).collect()
+    }
     pub fn counts_by_priority(&self) -> [u64; 5] {
         let mut c = [0u64; 5];
         for t in &self.list.items {
@@ -156,11 +445,55 @@ impl App {
         c
     }
 
+    /// Task counts per project, busiest first; tasks without a project are grouped last
+    pub fn counts_by_project(&self) -> Vec<(String, u64)> {
+        let mut counts: Vec<(String, u64)> = self
+            .list
+            .all_projects()
+            .into_iter()
+            .map(|p| {
+                let n = self.list.items.iter().filter(|t| t.project.as_deref().is_some_and(|tp| tp.eq_ignore_ascii_case(&p))).count();
+                (p, n as u64)
+            })
+            .collect();
+        counts.sort_by_key(|c| std::cmp::Reverse(c.1));
+        let none = self.list.items.iter().filter(|t| t.project.is_none()).count() as u64;
+        if none > 0 {
+            counts.push(("—".into(), none));
+        }
+        counts
+    }
+
+    /// Take the display settings from the user's config
+    pub fn apply_config(&mut self, config: &Config) {
+        self.tabs = Tabs::new(config.tabs.clone());
+        self.show_chart = config.show_chart;
+        self.enhanced_graphics = config.enhanced_graphics;
+        self.default_priority = config.default_priority;
+        self.reduced_motion = config.reduced_motion;
+        self.rollup_progress = config.rollup_progress;
+    }
+
+    /// Tasks completed on each of the last `days` days, oldest first
+    pub fn completions_per_day(&self, days: usize, now: i64) -> Vec<u64> {
+        let mut counts = vec![0; days];
+        for at in self.list.items.iter().filter_map(|t| t.completed_at) {
+            let age = (now - at).div_euclid(86_400);
+            if (0..days as i64).contains(&age) {
+                counts[days - 1 - age as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    /// Whether anything on screen moves, so the main loop needs to keep ticking
+    pub fn animating(&self) -> bool {
+        !self.reduced_motion
+            && (matches!(self.tabs.current(), Tab::Dash | Tab::World) || self.input_mode == InputMode::Insert)
+    }
+
     // animation tick
     pub fn on_tick(&mut self) {
-        self.progress += 0.01;
-        if self.progress > 1.0 { self.progress = 0.0; }
-
         let base = (self.percent_done() * 100.0) as u64;
         let wobble = ((self.pulse.sin() * 20.0) + 20.0) as u64;
         self.spark_points.remove(0);
@@ -174,7 +507,7 @@ impl App {
 
 // ------------------------ NYC paths loading & conversion ------------------------
 
-fn load_nyc_paths_and_bbox() -> (Option<MapPaths>, Option<([f64; 2], [f64; 2])>) {
+fn load_nyc_paths_and_bbox() -> (Option<MapPaths>, Option<MapBbox>) {
     use std::env;
     use std::fs;
 
@@ -193,11 +526,11 @@ fn load_nyc_paths_and_bbox() -> (Option<MapPaths>, Option<([f64; 2], [f64; 2])>)
                 return (Some(paths), Some(bbox));
             }
             // 2) Try GeoJSON and convert
-            if let Ok(fc) = serde_json::from_str::<GeoFeatureCollection>(&raw) {
-                if let Some(paths) = geojson_to_paths(&fc) {
-                    let bbox = compute_bbox(&paths);
-                    return (Some(paths), Some(bbox));
-                }
+            if let Ok(fc) = serde_json::from_str::<GeoFeatureCollection>(&raw)
+                && let Some(paths) = geojson_to_paths(&fc)
+            {
+                let bbox = compute_bbox(&paths);
+                return (Some(paths), Some(bbox));
             }
             // 3) Otherwise keep looking
         }
@@ -205,7 +538,7 @@ fn load_nyc_paths_and_bbox() -> (Option<MapPaths>, Option<([f64; 2], [f64; 2])>)
     (None, None)
 }
 
-fn compute_bbox(paths: &MapPaths) -> ([f64; 2], [f64; 2]) {
+fn compute_bbox(paths: &MapPaths) -> MapBbox {
     let mut min_lon = f64::INFINITY;
     let mut max_lon = f64::NEG_INFINITY;
     let mut min_lat = f64::INFINITY;
@@ -285,3 +618,38 @@ fn parse_multipolygon_coords(v: &serde_json::Value) -> Option<Vec<Vec<Vec<[f64;
     }
     Some(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduced_motion_stops_ticking_and_counts_real_completions() {
+        let mut list = TodoList::default();
+        list.add("a", 1, None).completed_at = Some(1_000_000 - 10);
+        list.add("b", 1, None).completed_at = Some(1_000_000 - 86_400 - 10);
+        list.add("c", 1, None).completed_at = Some(1_000_000 - 9 * 86_400);
+        let mut app = App::new(list);
+        assert_eq!(app.completions_per_day(3, 1_000_000), [0, 1, 1]);
+
+        app.tabs.next();
+        assert_eq!(app.tabs.current(), Tab::Dash);
+        assert!(app.animating());
+        app.reduced_motion = true;
+        assert!(!app.animating());
+    }
+
+    #[test]
+    fn rollup_progress_follows_the_config() {
+        let mut list = TodoList::default();
+        list.add("parent", 1, None);
+        let parent = list.items[0].id.clone();
+        list.add("done", 1, None).parent_id = Some(parent.clone());
+        list.add("open", 1, None).parent_id = Some(parent);
+        list.toggle_done_index(1);
+        let mut app = App::new(list);
+        assert_eq!(app.percent_done(), 0.5);
+        app.apply_config(&Config { rollup_progress: false, ..Config::default() });
+        assert!((app.percent_done() - 1.0 / 3.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,129 @@
+//! Mouse input, hit-tested against the areas recorded by the last `ui::draw`.
+
+use std::time::{Duration, Instant};
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+
+use crate::app::{input, keymap::Action, trash, App, InputMode, InsertField, MapView, Tab};
+
+/// Two clicks on the same row within this long count as a double-click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Clickable screen areas, rebuilt on every draw
+#[derive(Debug, Default, Clone)]
+pub struct HitAreas {
+    /// Header tab titles, in tab order
+    pub tabs: Vec<Rect>,
+    /// Body of the Todos table (inside the border) and the list row shown on its first line
+    pub table: Rect,
+    pub table_first_row: usize,
+    /// Notes section of the expanded panel
+    pub notes: Rect,
+    /// The 1–5 buttons in the add/edit overlay
+    pub priorities: Vec<Rect>,
+    /// World / NYC selector on the World tab
+    pub map_views: Vec<(MapView, Rect)>,
+}
+
+fn hit(rects: &[Rect], col: u16, row: u16) -> Option<usize> {
+    rects.iter().position(|r| r.contains(Position::new(col, row)))
+}
+
+pub fn handle_mouse(app: &mut App, ev: MouseEvent) {
+    let hits = app.hits.borrow().clone();
+    let (col, row) = (ev.column, ev.row);
+    let at = Position::new(col, row);
+    match (app.input_mode, ev.kind) {
+        (InputMode::Insert, MouseEventKind::Down(MouseButton::Left)) => {
+            if let Some(i) = hit(&hits.priorities, col, row) {
+                app.draft_priority = i as i8 + 1;
+                app.insert_field = InsertField::Priority;
+            }
+        }
+        (InputMode::Normal, MouseEventKind::Down(MouseButton::Left)) => {
+            if let Some(i) = hit(&hits.tabs, col, row) {
+                app.tabs.index = i;
+            } else if let Some(&(view, _)) = hits.map_views.iter().find(|(_, r)| r.contains(at)) {
+                app.map_view = view;
+            } else if hits.table.contains(at) {
+                let pos = hits.table_first_row + (row - hits.table.y) as usize;
+                if app.tabs.current() == Tab::Trash {
+                    app.trash_selected = pos.min(app.list.trash.len().saturating_sub(1));
+                } else {
+                    click_row(app, pos);
+                }
+            }
+        }
+        (InputMode::Help, MouseEventKind::ScrollDown) => app.help.scroll = (app.help.scroll + 1).min(app.help.scroll_max.get()),
+        (InputMode::Help, MouseEventKind::ScrollUp) => app.help.scroll = app.help.scroll.saturating_sub(1),
+        (InputMode::Normal, MouseEventKind::ScrollDown) if hits.notes.contains(at) => app.scroll_notes(1),
+        (InputMode::Normal, MouseEventKind::ScrollUp) if hits.notes.contains(at) => app.scroll_notes(-1),
+        (InputMode::Normal, MouseEventKind::ScrollDown) if hits.table.contains(at) => scroll_table(app, Action::SelectNext),
+        (InputMode::Normal, MouseEventKind::ScrollUp) if hits.table.contains(at) => scroll_table(app, Action::SelectPrev),
+        _ => {}
+    }
+}
+
+/// The wheel moves the selection, which the table keeps in view
+fn scroll_table(app: &mut App, action: Action) {
+    match (app.tabs.current() == Tab::Trash, action) {
+        (true, action) => {
+            trash::handle_action(app, action);
+        }
+        (false, Action::SelectNext) => app.select_next(),
+        (false, _) => app.select_prev(),
+    }
+}
+
+/// Select the row under the pointer; a second click on it toggles done
+fn click_row(app: &mut App, pos: usize) {
+    if pos >= app.visible_indices().len() {
+        return;
+    }
+    let now = Instant::now();
+    let double = app.last_click.is_some_and(|(at, p)| p == pos && now.duration_since(at) <= DOUBLE_CLICK);
+    if pos != app.selected {
+        app.selected = pos;
+        app.notes_scroll = 0;
+    }
+    if double {
+        input::toggle_selected(app);
+        app.last_click = None;
+    } else {
+        app.last_click = Some((now, pos));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::ui::draw, todolist::TodoList};
+    use crossterm::event::KeyModifiers;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn click(app: &mut App, column: u16, row: u16) {
+        let kind = MouseEventKind::Down(MouseButton::Left);
+        handle_mouse(app, MouseEvent { kind, column, row, modifiers: KeyModifiers::NONE });
+    }
+
+    #[test]
+    fn clicks_hit_drawn_rows_and_tabs() {
+        let mut list = TodoList::default();
+        list.add("First", 1, None);
+        list.add("Second", 1, None);
+        let mut app = App::new(list);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| draw(f, &app)).unwrap();
+
+        // the second row of the table body, clicked twice
+        let table = app.hits.borrow().table;
+        click(&mut app, table.x + 2, table.y + 1);
+        assert_eq!(app.selected, 1);
+        click(&mut app, table.x + 2, table.y + 1);
+        assert!(app.list.items[1].is_done());
+
+        click(&mut app, 10, 1); // " Dash " follows " Todos │"
+        assert_eq!(app.tabs.index, 1);
+    }
+}
@@ -0,0 +1,230 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::app::keymap::Key;
+
+/// Editing commands of a text field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Start,
+    End,
+    DeleteLeft,
+    DeleteRight,
+    DeleteWordLeft,
+    DeleteToStart,
+    DeleteToEnd,
+}
+
+/// Editing keys, as `handle_key` reads them, with their help text; other
+/// printable characters are typed
+pub const EDIT_KEYS: &[(Edit, &[&str], &str)] = &[
+    (Edit::Left, &["left"], "cursor left"),
+    (Edit::Right, &["right"], "cursor right"),
+    (Edit::WordLeft, &["ctrl-left", "alt-left"], "word left"),
+    (Edit::WordRight, &["ctrl-right", "alt-right"], "word right"),
+    (Edit::Start, &["home", "ctrl-a"], "start of line"),
+    (Edit::End, &["end", "ctrl-e"], "end of line"),
+    (Edit::DeleteLeft, &["backspace"], "delete before cursor"),
+    (Edit::DeleteRight, &["delete"], "delete at cursor"),
+    (Edit::DeleteWordLeft, &["ctrl-w", "ctrl-backspace", "alt-backspace"], "delete word"),
+    (Edit::DeleteToStart, &["ctrl-u"], "delete to start"),
+    (Edit::DeleteToEnd, &["ctrl-k"], "delete to end"),
+];
+
+/// Single-line text field with a cursor that moves by grapheme, so accents,
+/// emoji and CJK characters are edited as one unit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    /// Byte offset into `value`, always on a grapheme boundary
+    cursor: usize,
+}
+
+/// The part of a field that fits in the available width, split at the cursor
+#[derive(Debug, PartialEq, Eq)]
+pub struct Visible<'a> {
+    pub before: &'a str,
+    pub after: &'a str,
+}
+
+impl TextInput {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Replace the text and put the cursor at the end
+    pub fn set(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+        self.cursor = self.value.len();
+    }
+
+    pub fn clear(&mut self) {
+        self.set(String::new());
+    }
+
+    /// Insert text at the cursor; line breaks become spaces since the field is one line
+    pub fn insert_str(&mut self, text: &str) {
+        let text = text.replace("\r\n", " ").replace(['\n', '\r', '\t'], " ");
+        self.value.insert_str(self.cursor, &text);
+        self.cursor += text.len();
+        // an inserted combining mark may have merged with the previous grapheme
+        self.cursor = self.boundary_at_or_after(self.cursor);
+    }
+
+    /// Apply an editing key; returns false for keys the field doesn't use
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let mut key = Key::from(key);
+        key.modifiers.remove(KeyModifiers::SHIFT);
+        if let KeyCode::Char(c) = key.code
+            && key.modifiers.is_empty()
+        {
+            self.insert_str(c.encode_utf8(&mut [0; 4]));
+            return true;
+        }
+        let Some(&(edit, _, _)) = EDIT_KEYS.iter().find(|(_, keys, _)| keys.iter().any(|k| k.parse() == Ok(key))) else {
+            return false;
+        };
+        match edit {
+            Edit::Left => self.cursor = self.prev_boundary(),
+            Edit::Right => self.cursor = self.next_boundary(),
+            Edit::WordLeft => self.cursor = self.word_left(),
+            Edit::WordRight => self.cursor = self.word_right(),
+            Edit::Start => self.cursor = 0,
+            Edit::End => self.cursor = self.value.len(),
+            Edit::DeleteLeft => self.delete_to(self.prev_boundary()),
+            Edit::DeleteRight => self.delete_to(self.next_boundary()),
+            Edit::DeleteWordLeft => self.delete_to(self.word_left()),
+            Edit::DeleteToStart => self.delete_to(0),
+            Edit::DeleteToEnd => self.delete_to(self.value.len()),
+        }
+        true
+    }
+
+    /// Text around the cursor that fits in `width` columns, leaving one column
+    /// for the caret. Scrolls horizontally so the cursor stays in view.
+    pub fn visible(&self, width: usize) -> Visible<'_> {
+        let room = width.saturating_sub(1);
+        let (head, tail) = self.value.split_at(self.cursor);
+        // drop graphemes from the left until the text before the cursor fits
+        let mut start = 0;
+        let mut head_width = head.width();
+        let mut graphemes = head.grapheme_indices(true);
+        while head_width > room {
+            let Some((i, g)) = graphemes.next() else { break };
+            head_width -= g.width();
+            start = i + g.len();
+        }
+        // then fill the rest of the line after the cursor
+        let mut end = 0;
+        let mut used = head_width;
+        for (i, g) in tail.grapheme_indices(true) {
+            if used + g.width() > room {
+                break;
+            }
+            used += g.width();
+            end = i + g.len();
+        }
+        Visible { before: &head[start..], after: &tail[..end] }
+    }
+
+    fn delete_to(&mut self, to: usize) {
+        let (from, to) = (self.cursor.min(to), self.cursor.max(to));
+        self.value.replace_range(from..to, "");
+        self.cursor = from;
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.value[..self.cursor].grapheme_indices(true).next_back().map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.value[self.cursor..].graphemes(true).next().map_or(self.cursor, |g| self.cursor + g.len())
+    }
+
+    fn boundary_at_or_after(&self, pos: usize) -> usize {
+        std::iter::once(0)
+            .chain(self.value.grapheme_indices(true).map(|(i, g)| i + g.len()))
+            .find(|&end| end >= pos)
+            .unwrap_or(self.value.len())
+    }
+
+    /// Start of the word before the cursor, skipping whitespace first
+    fn word_left(&self) -> usize {
+        let mut pos = self.cursor;
+        let mut seen_word = false;
+        for (i, g) in self.value[..self.cursor].grapheme_indices(true).rev() {
+            let space = g.chars().all(char::is_whitespace);
+            if space && seen_word {
+                break;
+            }
+            seen_word |= !space;
+            pos = i;
+        }
+        pos
+    }
+
+    /// End of the word after the cursor, skipping whitespace first
+    fn word_right(&self) -> usize {
+        let mut pos = self.cursor;
+        let mut seen_word = false;
+        for g in self.value[self.cursor..].graphemes(true) {
+            let space = g.chars().all(char::is_whitespace);
+            if space && seen_word {
+                break;
+            }
+            seen_word |= !space;
+            pos += g.len();
+        }
+        pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn cursor_moves_by_grapheme_and_word() {
+        let mut input = TextInput::default();
+        input.insert_str("cafe\u{301} 日本 👍🏽");
+        input.handle_key(key(KeyCode::Left, KeyModifiers::NONE));
+        input.handle_key(key(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(input.value(), "cafe\u{301} 日本👍🏽");
+
+        input.handle_key(key(KeyCode::Home, KeyModifiers::NONE));
+        input.handle_key(key(KeyCode::Right, KeyModifiers::CONTROL));
+        input.handle_key(key(KeyCode::Left, KeyModifiers::NONE));
+        input.handle_key(key(KeyCode::Delete, KeyModifiers::NONE));
+        assert_eq!(input.value(), "caf 日本👍🏽");
+
+        input.handle_key(key(KeyCode::End, KeyModifiers::NONE));
+        input.handle_key(key(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert_eq!(input.value(), "caf ");
+        input.insert_str("line\nbreak");
+        input.handle_key(key(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn visible_window_follows_the_cursor() {
+        let mut input = TextInput::default();
+        input.set("日本語のテキスト");
+        // 8 double-width chars = 16 columns; 9 columns leave room for 4 of them
+        assert_eq!(input.visible(9), Visible { before: "テキスト", after: "" });
+        input.handle_key(key(KeyCode::Home, KeyModifiers::NONE));
+        assert_eq!(input.visible(9), Visible { before: "", after: "日本語の" });
+    }
+}
@@ -0,0 +1,277 @@
+//! Colors used by every draw function. Built-in palettes are picked by name
+//! with the `theme` setting; anything else names a user theme, read from
+//! `themes/<name>.toml` in the config directory (or a path to a `.toml` file):
+//!
+//! ```toml
+//! base = "light"        # built-in palette to start from (default: dark)
+//! accent = "#af5f00"    # names, hex or 0-255 indices
+//! danger = "red"
+//! ```
+//!
+//! With `NO_COLOR` set, every color is dropped and only modifiers remain.
+
+use std::{fs, path::Path, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use ratatui::style::{Color, Modifier, Style};
+
+macro_rules! theme {
+    ($($field:ident: $doc:literal;)+) => {
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Theme {
+            $(#[doc = $doc] pub $field: Color,)+
+            /// Draw with modifiers only (`NO_COLOR`)
+            pub no_color: bool,
+        }
+
+        impl Theme {
+            /// Color names accepted in a theme file
+            pub const KEYS: &[&str] = &[$(stringify!($field)),+];
+
+            fn set(&mut self, key: &str, color: Color) -> bool {
+                match key {
+                    $(stringify!($field) => self.$field = color,)+
+                    _ => return false,
+                }
+                true
+            }
+        }
+    };
+}
+
+theme! {
+    accent: "Selection, active tabs, labels and headings";
+    tab: "Inactive tab titles";
+    project: "`@project` tokens and links";
+    tag: "`+tag` tokens, sub-headings and list markers";
+    danger: "Overdue tasks";
+    success: "Done checkboxes, activity and the priority chart";
+    muted: "Secondary text: timeframes, hints, due dates";
+    faint: "Closed or blocked tasks and placeholders";
+    marked: "Tasks marked for a bulk action";
+    in_progress: "Tasks in progress";
+    highlight: "Search matches, the chosen priority and the completion gauge";
+    contrast: "Text drawn on top of bars, and the gauge background";
+    map: "Map outlines and labels";
+    code: "Inline code and code blocks in notes";
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    /// Built-in palettes, as named in the `theme` setting
+    pub const BUILTIN: &[&str] = &["dark", "light", "solarized", "high-contrast", "colorblind"];
+
+    pub fn builtin(name: &str) -> Option<Theme> {
+        Some(match name {
+            "dark" => Theme::dark(),
+            "light" => Theme::light(),
+            "solarized" => Theme::solarized(),
+            "high-contrast" => Theme::high_contrast(),
+            "colorblind" => Theme::colorblind(),
+            _ => return None,
+        })
+    }
+
+    pub fn dark() -> Theme {
+        Theme {
+            accent: Color::Yellow,
+            tab: Color::Green,
+            project: Color::Blue,
+            tag: Color::Cyan,
+            danger: Color::Red,
+            success: Color::Green,
+            muted: Color::Gray,
+            faint: Color::DarkGray,
+            marked: Color::LightCyan,
+            in_progress: Color::LightGreen,
+            highlight: Color::Magenta,
+            contrast: Color::Black,
+            map: Color::White,
+            code: Color::LightYellow,
+            no_color: false,
+        }
+    }
+
+    /// Darker tones that stay readable on a white background
+    pub fn light() -> Theme {
+        Theme {
+            accent: Color::Rgb(175, 95, 0),
+            tab: Color::Rgb(0, 110, 0),
+            project: Color::Rgb(0, 80, 170),
+            tag: Color::Rgb(0, 110, 110),
+            danger: Color::Rgb(190, 0, 0),
+            success: Color::Rgb(0, 120, 0),
+            muted: Color::Rgb(90, 90, 90),
+            faint: Color::Rgb(150, 150, 150),
+            marked: Color::Rgb(0, 120, 170),
+            in_progress: Color::Rgb(0, 130, 60),
+            highlight: Color::Rgb(160, 0, 140),
+            contrast: Color::White,
+            map: Color::Rgb(60, 60, 60),
+            code: Color::Rgb(140, 80, 0),
+            no_color: false,
+        }
+    }
+
+    /// Ethan Schoonover's Solarized accents, for either background
+    pub fn solarized() -> Theme {
+        Theme {
+            accent: Color::Rgb(0xb5, 0x89, 0x00),
+            tab: Color::Rgb(0x85, 0x99, 0x00),
+            project: Color::Rgb(0x26, 0x8b, 0xd2),
+            tag: Color::Rgb(0x2a, 0xa1, 0x98),
+            danger: Color::Rgb(0xdc, 0x32, 0x2f),
+            success: Color::Rgb(0x85, 0x99, 0x00),
+            muted: Color::Rgb(0x83, 0x94, 0x96),
+            faint: Color::Rgb(0x58, 0x6e, 0x75),
+            marked: Color::Rgb(0x6c, 0x71, 0xc4),
+            in_progress: Color::Rgb(0x2a, 0xa1, 0x98),
+            highlight: Color::Rgb(0xd3, 0x36, 0x82),
+            contrast: Color::Rgb(0x00, 0x2b, 0x36),
+            map: Color::Rgb(0x93, 0xa1, 0xa1),
+            code: Color::Rgb(0xcb, 0x4b, 0x16),
+            no_color: false,
+        }
+    }
+
+    /// Bright colors only, and no dark gray for text
+    pub fn high_contrast() -> Theme {
+        Theme {
+            accent: Color::LightYellow,
+            tab: Color::White,
+            project: Color::LightBlue,
+            tag: Color::LightCyan,
+            danger: Color::LightRed,
+            success: Color::LightGreen,
+            muted: Color::White,
+            faint: Color::Gray,
+            marked: Color::LightMagenta,
+            in_progress: Color::LightGreen,
+            highlight: Color::LightMagenta,
+            contrast: Color::Black,
+            map: Color::White,
+            code: Color::LightYellow,
+            no_color: false,
+        }
+    }
+
+    /// The Okabe–Ito palette, which stays distinct under common color blindness;
+    /// overdue (vermillion) and in progress (sky blue) never rely on red vs green
+    pub fn colorblind() -> Theme {
+        Theme {
+            accent: Color::Rgb(0xf0, 0xe4, 0x42),
+            tab: Color::Rgb(0xe6, 0x9f, 0x00),
+            project: Color::Rgb(0x56, 0xb4, 0xe9),
+            tag: Color::Rgb(0x00, 0x9e, 0x73),
+            danger: Color::Rgb(0xd5, 0x5e, 0x00),
+            success: Color::Rgb(0x00, 0x9e, 0x73),
+            muted: Color::Gray,
+            faint: Color::DarkGray,
+            marked: Color::Rgb(0xcc, 0x79, 0xa7),
+            in_progress: Color::Rgb(0x56, 0xb4, 0xe9),
+            highlight: Color::Rgb(0xcc, 0x79, 0xa7),
+            contrast: Color::Black,
+            map: Color::White,
+            code: Color::Rgb(0xe6, 0x9f, 0x00),
+            no_color: false,
+        }
+    }
+
+    /// A built-in palette by name, else the user theme `name` from `themes_dir`
+    /// (or the file at `name` when it looks like a path)
+    pub fn load(name: &str, themes_dir: &Path) -> Result<Theme> {
+        if let Some(theme) = Theme::builtin(name) {
+            return Ok(theme);
+        }
+        let path = if name.contains('/') || name.ends_with(".toml") {
+            Path::new(name).to_path_buf()
+        } else {
+            themes_dir.join(format!("{name}.toml"))
+        };
+        if !path.exists() {
+            bail!("unknown theme '{name}'; built-in themes are {}, or add {}", Theme::BUILTIN.join(", "), path.display());
+        }
+        let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        Theme::parse(&text).with_context(|| format!("in {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Theme> {
+        let mut table: toml::Table = toml::from_str(text)?;
+        let mut theme = match table.remove("base") {
+            None => Theme::dark(),
+            Some(toml::Value::String(base)) => match Theme::builtin(&base) {
+                Some(theme) => theme,
+                None => bail!("unknown base theme '{base}'; use one of {}", Theme::BUILTIN.join(", ")),
+            },
+            Some(other) => bail!("base must be a theme name, got {other}"),
+        };
+        for (key, value) in table {
+            let toml::Value::String(raw) = value else { bail!("{key} must be a color string, got {value}") };
+            let color = Color::from_str(&raw).map_err(|_| anyhow::anyhow!("{key}: '{raw}' is not a color"))?;
+            if !theme.set(&key, color) {
+                bail!("unknown color '{key}'; valid colors are: {}", Theme::KEYS.join(", "));
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Honor `NO_COLOR` (https://no-color.org): set and non-empty turns colors off
+    pub fn apply_no_color(self) -> Theme {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        Theme { no_color: self.no_color || no_color, ..self }
+    }
+
+    /// `color`, or the terminal default with `NO_COLOR`
+    pub fn color(&self, color: Color) -> Color {
+        if self.no_color { Color::Reset } else { color }
+    }
+
+    /// Text in `color`; plain text with `NO_COLOR`
+    pub fn fg(&self, color: Color) -> Style {
+        if self.no_color { Style::default() } else { Style::default().fg(color) }
+    }
+
+    /// Text in `color`; with `NO_COLOR`, `modifier` tells it apart instead
+    pub fn fg_or(&self, color: Color, modifier: Modifier) -> Style {
+        if self.no_color { Style::default().add_modifier(modifier) } else { Style::default().fg(color) }
+    }
+
+    /// `contrast` text on a `color` background, as for bar values
+    pub fn on(&self, color: Color) -> Style {
+        if self.no_color { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default().fg(self.contrast).bg(color) }
+    }
+
+    /// The selected row or item; reversed with `NO_COLOR` so it still stands out
+    pub fn selected(&self) -> Style {
+        let style = self.fg(self.accent).add_modifier(Modifier::BOLD);
+        if self.no_color { style.add_modifier(Modifier::REVERSED) } else { style }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_themes_extend_a_base_and_reject_typos() {
+        let theme = Theme::parse("base = \"light\"\naccent = \"#ff8800\"\ndanger = \"light-red\"\n").unwrap();
+        assert_eq!(theme.accent, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.danger, Color::LightRed);
+        assert_eq!(theme.tag, Theme::light().tag);
+
+        assert!(Theme::parse("acent = \"red\"").unwrap_err().to_string().contains("unknown color 'acent'"));
+        assert!(Theme::parse("accent = \"reddish\"").unwrap_err().to_string().contains("not a color"));
+        assert!(Theme::builtin("high-contrast").is_some());
+
+        let plain = Theme { no_color: true, ..Theme::dark() };
+        assert_eq!(plain.fg(plain.danger), Style::default());
+        assert!(plain.selected().add_modifier.contains(Modifier::REVERSED));
+        assert_eq!(plain.fg_or(plain.marked, Modifier::UNDERLINED), Style::default().add_modifier(Modifier::UNDERLINED));
+        assert_eq!(Theme::dark().fg_or(Color::Cyan, Modifier::UNDERLINED), Style::default().fg(Color::Cyan));
+    }
+}
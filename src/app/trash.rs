@@ -0,0 +1,65 @@
+//! The Trash tab: deleted tasks, newest first, with restore and purge.
+
+use crate::app::{keymap::Action, App};
+
+/// Actions the Trash tab handles itself, with what they do here (for the `?` overlay)
+pub const ACTIONS: &[(Action, &str)] = &[
+    (Action::Restore, "restore"),
+    (Action::ToggleDone, "restore"),
+    (Action::Delete, "purge"),
+    (Action::EmptyTrash, "empty trash"),
+];
+
+/// Trash indices in display order (most recently deleted first)
+pub fn rows(app: &App) -> Vec<usize> {
+    let mut rows: Vec<usize> = (0..app.list.trash.len()).collect();
+    rows.sort_by_key(|&i| std::cmp::Reverse(app.list.trash[i].deleted_at));
+    rows
+}
+
+fn selected(app: &App) -> Option<usize> {
+    rows(app).get(app.trash_selected).copied()
+}
+
+/// Actions on the Trash tab; returns false for ones it leaves to the normal handler
+pub fn handle_action(app: &mut App, action: Action) -> bool {
+    match action {
+        Action::SelectNext => app.trash_selected = (app.trash_selected + 1).min(app.list.trash.len().saturating_sub(1)),
+        Action::SelectPrev => app.trash_selected = app.trash_selected.saturating_sub(1),
+        Action::SelectFirst => app.trash_selected = 0,
+        Action::SelectLast => app.trash_selected = app.list.trash.len().saturating_sub(1),
+        Action::ToggleDone | Action::Restore => {
+            let Some(idx) = selected(app) else { return true };
+            let before = app.list.clone();
+            let title = app.list.trash[idx].title.clone();
+            let n = app.list.restore(idx);
+            app.status_line = format!("Restored \"{title}\"{} ✓", with_subtasks(n));
+            app.record(format!("Restore \"{title}\""), before);
+        }
+        Action::Delete => {
+            let Some(idx) = selected(app) else { return true };
+            let before = app.list.clone();
+            let title = app.list.trash[idx].title.clone();
+            let n = app.list.purge(idx);
+            app.status_line = format!("Purged \"{title}\"{} (u to undo)", with_subtasks(n));
+            app.record(format!("Purge \"{title}\""), before);
+        }
+        Action::EmptyTrash => {
+            let before = app.list.clone();
+            let n = app.list.empty_trash();
+            if n > 0 {
+                app.status_line = format!("Emptied trash: {n} tasks purged (u to undo)");
+                app.record("Empty trash", before);
+            } else {
+                app.status_line = "Trash is empty".into();
+            }
+        }
+        _ => return false,
+    }
+    app.trash_selected = app.trash_selected.min(app.list.trash.len().saturating_sub(1));
+    true
+}
+
+fn with_subtasks(n: usize) -> String {
+    if n > 1 { format!(" with {} subtasks", n - 1) } else { String::new() }
+}
@@ -1,17 +1,24 @@
 use ratatui::{
     prelude::*,
     widgets::{
-        Block, Borders, Tabs, Paragraph, Wrap, BarChart, Gauge, LineGauge, Clear, Table, Row, Cell,
+        Block, Borders, Tabs, Paragraph, Wrap, Bar, BarChart, BarGroup, Gauge, LineGauge, Clear, Table, Row, Cell,
         canvas::{Canvas, Map, MapResolution, Line as CanvasLine},
     },
     text::{Span, Line as TextLine},
     symbols,
 };
-use crate::app::{App, InputMode, InsertField, MapView};
+use chrono::{Local, TimeZone};
+
+use crate::app::{help, keymap::Action, markdown, mouse::HitAreas, textinput::TextInput, theme::Theme, trash, App, InputMode, Tab, InsertField, MapView};
+use unicode_width::UnicodeWidthStr;
+use crate::due;
 use crate::task::Status;
+use crate::todolist::TaskFilter;
 
 pub fn draw(frame: &mut Frame, app: &App) {
-    let size = frame.size();
+    let size = frame.area();
+    let theme = &app.theme;
+    *app.hits.borrow_mut() = HitAreas::default();
 
     let outer = Layout::default()
         .direction(Direction::Vertical)
@@ -21,17 +28,17 @@ pub fn draw(frame: &mut Frame, app: &App) {
     // Tabs header
     let titles: Vec<TextLine> = app
         .tabs
-        .titles
-        .iter()
+        .titles()
+        .into_iter()
         .enumerate()
         .map(|(i, t)| {
             let active = i == app.tabs.index;
             TextLine::from(Span::styled(
-                *t,
+                t,
                 if active {
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                    theme.fg(theme.accent).add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default().fg(Color::Green)
+                    theme.fg(theme.tab)
                 },
             ))
         })
@@ -40,49 +47,177 @@ pub fn draw(frame: &mut Frame, app: &App) {
     let tabs = Tabs::new(titles)
         .block(Block::default().borders(Borders::ALL).title("todo-tui"))
         .select(app.tabs.index)
-        .highlight_style(Style::default().fg(Color::Yellow));
+        .highlight_style(theme.fg(theme.accent));
     frame.render_widget(tabs, outer[0]);
+    app.hits.borrow_mut().tabs = tab_rects(outer[0], &app.tabs.titles());
 
     // Main content by tab
-    match app.tabs.index {
-        0 => draw_todos(frame, app, outer[1]),
-        1 => draw_dash(frame, app, outer[1]),
-        2 => draw_world(frame, app, outer[1]),
-        _ => {}
+    match app.tabs.current() {
+        Tab::Todos => draw_todos(frame, app, outer[1]),
+        Tab::Dash => draw_dash(frame, app, outer[1]),
+        Tab::World => draw_world(frame, app, outer[1]),
+        Tab::Trash => draw_trash(frame, app, outer[1]),
     }
 
+    // the `?` overlay sits on top of whatever it was opened from
+    let mode = if app.input_mode == InputMode::Help { app.help.return_to } else { app.input_mode };
+
     // INSERT OVERLAY: visible while typing on any tab
-    if matches!(app.input_mode, InputMode::Insert) {
+    if matches!(mode, InputMode::Insert) {
         draw_insert_overlay(frame, app, outer[1]);
     }
+    if matches!(mode, InputMode::ConfirmDelete) {
+        draw_confirm_delete(frame, app, outer[1]);
+    }
+    if matches!(mode, InputMode::ConfirmBulkDelete) {
+        draw_confirm_bulk_delete(frame, app, outer[1]);
+    }
+    if matches!(mode, InputMode::PickStatus) {
+        draw_status_picker(frame, app, outer[1]);
+    }
+    if matches!(mode, InputMode::BulkTag) {
+        draw_bulk_tag(frame, app, outer[1]);
+    }
+    if matches!(app.input_mode, InputMode::Help) {
+        draw_help(frame, app, size);
+    }
+}
+
+/// Where a bordered `Tabs` widget puts each title, padding included
+fn tab_rects(area: Rect, titles: &[&str]) -> Vec<Rect> {
+    let inner = area.inner(Margin::new(1, 1));
+    let mut x = inner.x;
+    titles
+        .iter()
+        .map(|t| {
+            // " title " followed by a one-column divider
+            let w = t.width() as u16 + 2;
+            let r = Rect::new(x, inner.y, w, 1).intersection(inner);
+            x = x.saturating_add(w + 1);
+            r
+        })
+        .collect()
 }
 
 // =================== TAB 0: TODOS ==========================================
+/// Actions listed in the footer, with whatever keys the keymap gives them
+const TODOS_HELP: &[Action] = &[
+    Action::Help,
+    Action::Quit,
+    Action::Add,
+    Action::AddSubtask,
+    Action::ToggleDone,
+    Action::ToggleTree,
+    Action::Edit,
+    Action::EditNotes,
+    Action::PickStatus,
+    Action::Delete,
+    Action::Undo,
+    Action::Redo,
+    Action::Mark,
+    Action::MarkRange,
+    Action::MoveUp,
+    Action::MoveDown,
+    Action::CycleSort,
+    Action::FlipSort,
+    Action::Fold,
+    Action::LinkBlocker,
+    Action::Save,
+    Action::ToggleExpand,
+    Action::ScrollNotesDown,
+    Action::ScrollNotesUp,
+    Action::Search,
+    Action::FilterStatus,
+    Action::ClearFilters,
+    Action::FilterTag,
+    Action::FilterProject,
+    Action::NextTab,
+    Action::PrevTab,
+    Action::ToggleChart,
+    Action::ToggleGraphics,
+    Action::ToggleMapView,
+];
+
+const TRASH_HELP: &[Action] =
+    &[Action::Help, Action::SelectNext, Action::SelectPrev, Action::Restore, Action::Delete, Action::EmptyTrash, Action::Undo];
+
 fn draw_todos(frame: &mut Frame, app: &App, area: Rect) {
-    // If expanded, reserve taller footer; else compact status
-    let footer_h = if app.expanded { 7 } else { 3 };
+    // If expanded, give the details half the screen; else just enough for help + status
+    let help = Paragraph::new(vec![
+        TextLine::from(app.keymap.help_line(TODOS_HELP)),
+        TextLine::from(status_text(app)),
+    ])
+    .wrap(Wrap { trim: true })
+    .block(Block::default().borders(Borders::ALL).title("Status"));
+    let footer_h = if app.expanded {
+        (area.height / 2).max(7)
+    } else {
+        (help.line_count(area.width) as u16).clamp(3, (area.height / 3).max(3))
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(1), Constraint::Length(footer_h)].as_ref())
         .split(area);
 
-    // Build rows for Table with two columns: [P#] title | timeframe
-    let visible = app.visible_indices();
+    // Build rows for Table with three columns: [P#] title | timeframe | relative due
+    let theme = &app.theme;
+    let now = Local::now().timestamp();
+    let visible = app.visible_rows();
     let mut rows: Vec<Row> = Vec::with_capacity(visible.len());
-    for (list_row, &idx) in visible.iter().enumerate() {
+    for (list_row, &(idx, depth)) in visible.iter().enumerate() {
         let t = &app.list.items[idx];
-        let left = format!("[P{}] {}", t.priority, t.title);
+        let repeat_mark = if t.recurrence.is_some() { "↻ " } else { "" };
+        // subtree marker: ▾ expanded, ▸ collapsed, blank for leaves
+        let fold = if !app.list.has_children(idx) {
+            "  "
+        } else if app.collapsed.contains(&t.id) {
+            "▸ "
+        } else {
+            "▾ "
+        };
+        let indent = "  ".repeat(depth);
+        let marked = app.is_marked(&t.id);
+        let mark = if marked { "● " } else { "" };
+        let blocked = !t.is_closed() && app.list.is_blocked(idx);
+        let lock = if blocked { "🔒 " } else { "" };
+        let mut left = vec![Span::raw(format!("{indent}{fold}{mark}{} [P{}] {lock}{repeat_mark}", t.status.mark(), t.priority))];
+        left.extend(highlighted(theme, &t.title, &app.filter.highlights(&t.title), Style::default()));
+        if let Some(p) = &t.project {
+            left.push(Span::styled(format!(" @{p}"), theme.fg(theme.project)));
+        }
+        for tag in &t.tags {
+            left.push(Span::styled(format!(" +{tag}"), theme.fg(theme.tag)));
+        }
         let right = t.timeframe.as_deref().unwrap_or("—");
+        let overdue = t.is_overdue(now);
+        let due_text = t.due.map(|d| due::relative(d, now)).unwrap_or_default();
+        let due_style = if overdue {
+            theme.fg(theme.danger).add_modifier(Modifier::BOLD)
+        } else {
+            theme.fg(theme.muted)
+        };
         let mut row = Row::new(vec![
-            Cell::from(left),
-            Cell::from(Span::styled(right.to_string(), Style::default().fg(Color::Gray))),
+            Cell::from(TextLine::from(left)),
+            Cell::from(TextLine::from(highlighted(theme, right, &app.filter.highlights(right), theme.fg(theme.muted)))),
+            Cell::from(Span::styled(due_text, due_style)),
         ]);
 
-        // highlight selected row in yellow
+        // highlight the selected row in the accent color, overdue rows in danger;
+        // without colors, marked, done and in-progress rows get a modifier each
         if list_row == app.selected {
-            row = row.style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+            row = row.style(theme.selected());
+        } else if marked {
+            row = row.style(theme.fg_or(theme.marked, Modifier::UNDERLINED));
         } else if t.status == Status::Done {
-            row = row.style(Style::default().fg(Color::DarkGray));
+            row = row.style(theme.fg_or(theme.faint, Modifier::DIM));
+        } else if t.status == Status::Cancelled {
+            row = row.style(theme.fg(theme.faint).add_modifier(Modifier::CROSSED_OUT));
+        } else if t.status == Status::InProgress {
+            row = row.style(theme.fg_or(theme.in_progress, Modifier::ITALIC));
+        } else if blocked {
+            row = row.style(theme.fg(theme.faint).add_modifier(Modifier::DIM));
+        } else if overdue {
+            row = row.style(theme.fg(theme.danger));
         }
         rows.push(row);
     }
@@ -106,61 +241,192 @@ fn draw_todos(frame: &mut Frame, app: &App, area: Rect) {
     // Table::new expects (rows, columns) in your ratatui version.
     let table = Table::new(
         rows[start..end].to_vec(),
-        [Constraint::Percentage(70), Constraint::Percentage(30)],
+        [Constraint::Percentage(58), Constraint::Percentage(28), Constraint::Percentage(14)],
     )
-    .block(Block::default().borders(Borders::ALL).title("Todos"))
+    .block(Block::default().borders(Borders::ALL).title(todos_title(app)))
     .column_spacing(2);
 
     frame.render_widget(table, chunks[0]);
+    let mut hits = app.hits.borrow_mut();
+    hits.table = chunks[0].inner(Margin::new(1, 1));
+    hits.table_first_row = start;
+    drop(hits);
 
     // Footer: status (and expanded details if toggled)
     if app.expanded {
         draw_expanded_details(frame, app, chunks[1]);
     } else {
-        let help = Paragraph::new(vec![
-            TextLine::from("q quit | a add | Enter toggle done | d delete | ↑/↓ move | s save"),
-            TextLine::from("Space expand/collapse | Tabs: h/l or ←/→ or [Tab] | t toggle chart | g graphics | m map view"),
-            TextLine::from(format!("Status: {}", app.status_line)),
-        ])
-        .wrap(Wrap { trim: true })
-        .block(Block::default().borders(Borders::ALL).title("Status"));
         frame.render_widget(help, chunks[1]);
     }
 }
 
+fn status_text(app: &App) -> String {
+    let marked = app.marked_ids().len();
+    if marked > 0 {
+        format!("Status: {}  [{marked} marked]", app.status_line)
+    } else {
+        format!("Status: {}", app.status_line)
+    }
+}
+
+fn todos_title(app: &App) -> String {
+    let sort = format!("Todos · {}", app.sort);
+    if app.input_mode == InputMode::Search {
+        let rest = TaskFilter { query: String::new(), ..app.filter.clone() }.describe();
+        let rest = if rest.is_empty() { rest } else { format!(" · {rest}") };
+        format!("{sort} [/{}▏{rest}]", app.filter.query)
+    } else if app.filter.is_empty() {
+        sort
+    } else {
+        format!("{sort} [{}]", app.filter.describe())
+    }
+}
+
+/// Split `text` into spans, underlining the chars at `hits` (search matches)
+fn highlighted<'a>(theme: &Theme, text: &'a str, hits: &[usize], base: Style) -> Vec<Span<'a>> {
+    if hits.is_empty() {
+        return vec![Span::styled(text, base)];
+    }
+    let hit_style = base.patch(theme.fg(theme.highlight)).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut current = false;
+    for (n, (byte, _)) in text.char_indices().enumerate() {
+        let hit = hits.binary_search(&n).is_ok();
+        if hit != current && byte > start {
+            spans.push(Span::styled(&text[start..byte], if current { hit_style } else { base }));
+            start = byte;
+        }
+        current = hit;
+    }
+    spans.push(Span::styled(&text[start..], if current { hit_style } else { base }));
+    spans
+}
+
 // expanded panel under the list
 fn draw_expanded_details(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let mut lines: Vec<TextLine> = Vec::new();
+    let mut notes = Vec::new();
     if let Some(idx) = app.visible_indices().get(app.selected).cloned() {
         let t = &app.list.items[idx];
         lines.push(TextLine::from(Span::styled(
             "Details",
-            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            theme.fg(theme.accent).add_modifier(Modifier::BOLD),
         )));
-        lines.push(TextLine::from(format!("Title: {}", t.title)));
+        let mut title = format!("Title: {}", t.title);
+        if let Some(p) = &t.project {
+            title.push_str(&format!("  @{p}"));
+        }
+        for tag in &t.tags {
+            title.push_str(&format!(" +{tag}"));
+        }
+        lines.push(TextLine::from(title));
         lines.push(TextLine::from(format!("Priority: {}", t.priority)));
-        lines.push(TextLine::from(format!("Status: {:?}", t.status)));
-        lines.push(TextLine::from(format!(
-            "Timeframe: {}",
-            t.timeframe.as_deref().unwrap_or("<none>")
-        )));
-        lines.push(TextLine::from("Notes:"));
-        lines.push(TextLine::from(
-            t.notes.clone().unwrap_or_else(|| "<none>".to_string()),
-        ));
+        let mut status = format!("Status: {}", t.status);
+        let fmt_time = |ts: i64| {
+            Local.timestamp_opt(ts, 0).single().map(|dt| dt.format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+        };
+        if let Some(s) = t.started_at {
+            status.push_str(&format!("  ·  Started: {}", fmt_time(s)));
+        }
+        if let Some(c) = t.completed_at {
+            status.push_str(&format!("  ·  Completed: {}", fmt_time(c)));
+        }
+        if let Some(r) = &t.recurrence {
+            status.push_str(&format!("  ·  Repeats: {r}"));
+        }
+        let children = app.list.children(idx);
+        if !children.is_empty() {
+            let done = children.iter().filter(|&&c| app.list.items[c].is_done()).count();
+            status.push_str(&format!(
+                "  ·  Subtasks: {done}/{} done ({:.0}%)",
+                children.len(),
+                app.list.progress(idx) * 100.0
+            ));
+        }
+        lines.push(TextLine::from(status));
+
+        let names = |ids: Vec<usize>| {
+            ids.iter()
+                .map(|&i| {
+                    let mark = if app.list.items[i].is_closed() { "✓" } else { "…" };
+                    format!("{} {mark}", app.list.items[i].title)
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let (upstream, downstream) = (app.list.blockers(idx), app.list.dependents(idx));
+        if !upstream.is_empty() || !downstream.is_empty() {
+            let mut deps = Vec::new();
+            if !upstream.is_empty() {
+                deps.push(format!("Blocked by: {}", names(upstream)));
+            }
+            if !downstream.is_empty() {
+                deps.push(format!("Blocks: {}", names(downstream)));
+            }
+            lines.push(TextLine::from(deps.join("  ·  ")));
+        }
+        let mut tf_spans = vec![Span::raw(format!("Timeframe: {}", t.timeframe.as_deref().unwrap_or("<none>")))];
+        if let Some(d) = t.due {
+            let now = Local::now().timestamp();
+            let fmt = |ts: i64| {
+                Local.timestamp_opt(ts, 0).single().map(|dt| dt.format("%a %Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+            };
+            let mut text = format!("  ·  Due: {}", fmt(d));
+            if let Some(e) = t.due_end {
+                text.push_str(&format!(" → {}", fmt(e)));
+            }
+            text.push_str(&format!(" ({})", due::relative(d, now)));
+            let style = theme.fg(if t.is_overdue(now) { theme.danger } else { theme.muted });
+            tf_spans.push(Span::styled(text, style));
+        }
+        lines.push(TextLine::from(tf_spans));
+        notes = t.notes.as_deref().map(|n| markdown::render(n, theme)).unwrap_or_default();
     } else {
         lines.push(TextLine::from("No task selected."));
     }
-    let p = Paragraph::new(lines)
-        .wrap(Wrap { trim: true })
-        .block(Block::default().borders(Borders::ALL).title("Expanded"));
-    frame.render_widget(p, area);
+
+    // the footer help is hidden here, so at least point at the overlay
+    let help_keys = app.keymap.keys_for(Action::Help);
+    let title = if help_keys.is_empty() { "Expanded".to_string() } else { format!("Expanded · {help_keys} help") };
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let details = Paragraph::new(lines).wrap(Wrap { trim: true });
+    // details keep their height; notes get whatever is left and scroll on their own
+    let details_h = (details.line_count(inner.width) as u16).min(inner.height);
+    let [details_area, notes_area] =
+        Layout::vertical([Constraint::Length(details_h), Constraint::Min(0)]).areas(inner);
+    frame.render_widget(details, details_area);
+    if notes_area.height < 2 {
+        app.notes_scroll_max.set(0);
+        return;
+    }
+
+    let notes_p = Paragraph::new(if notes.is_empty() { vec![TextLine::from("<none>")] } else { notes })
+        .wrap(Wrap { trim: false });
+    let body_h = notes_area.height - 1;
+    let total = notes_p.line_count(notes_area.width) as u16;
+    app.notes_scroll_max.set(total.saturating_sub(body_h));
+    let scroll = app.notes_scroll.min(app.notes_scroll_max.get());
+    let mut header = vec![Span::styled("Notes", Style::default().add_modifier(Modifier::BOLD))];
+    if total > body_h {
+        header.push(Span::styled(
+            format!("  lines {}-{} of {total} · PgUp/PgDn scroll", scroll + 1, (scroll + body_h).min(total)),
+            theme.fg(theme.faint),
+        ));
+    }
+    let [header_area, body_area] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(notes_area);
+    app.hits.borrow_mut().notes = body_area;
+    frame.render_widget(Paragraph::new(TextLine::from(header)), header_area);
+    frame.render_widget(notes_p.scroll((scroll, 0)), body_area);
 }
 
 // =================== INSERT OVERLAY ========================================
-fn draw_insert_overlay(frame: &mut Frame, app: &App, content_area: Rect) {
-    // 4 content lines (Title, Notes, Timeframe, Priority) => box height 6 incl. borders
-    let box_height = 6;
+fn draw_insert_overlay<'a>(frame: &mut Frame, app: &'a App, content_area: Rect) {
+    // 5 content lines (Title, Notes, Timeframe, Repeat, Priority) => box height 7 incl. borders
+    let box_height = 7;
     let box_width = content_area.width.saturating_sub(4);
     let x = content_area.x + 2;
     let y = content_area.y + content_area.height.saturating_sub(box_height + 1);
@@ -168,50 +434,41 @@ fn draw_insert_overlay(frame: &mut Frame, app: &App, content_area: Rect) {
     let rect = Rect { x, y, width: box_width, height: box_height };
 
     // Blink caret for text fields
-    let caret_visible = app.pulse.sin() > 0.0;
+    let caret_visible = app.reduced_motion || app.pulse.sin() > 0.0;
     let caret = if caret_visible { "▏" } else { " " };
 
-    let label_active = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
-    let label_inactive = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
-
-    // Title
-    let title_label_style = if matches!(app.insert_field, InsertField::Title) { label_active } else { label_inactive };
-    let title_span = if app.draft_title.is_empty() {
-        Span::styled("<type a title>", Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC))
-    } else {
-        Span::raw(app.draft_title.clone())
-    };
-    let title_line = TextLine::from(vec![
-        Span::styled("Title: ", title_label_style),
-        title_span,
-        if matches!(app.insert_field, InsertField::Title) { Span::raw(caret) } else { Span::raw("") },
-    ]);
-
-    // Notes
-    let notes_label_style = if matches!(app.insert_field, InsertField::Notes) { label_active } else { label_inactive };
-    let notes_span = if app.draft_notes.is_empty() {
-        Span::styled("<add optional notes>", Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC))
-    } else {
-        Span::raw(app.draft_notes.clone())
-    };
-    let notes_line = TextLine::from(vec![
-        Span::styled("Notes: ", notes_label_style),
-        notes_span,
-        if matches!(app.insert_field, InsertField::Notes) { Span::raw(caret) } else { Span::raw("") },
-    ]);
-
-    // Timeframe
-    let tf_label_style = if matches!(app.insert_field, InsertField::Time) { label_active } else { label_inactive };
-    let tf_span = if app.draft_timeframe.is_empty() {
-        Span::styled("<e.g. Today 3–5pm | 2025-09-10 09:00>", Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC))
-    } else {
-        Span::raw(app.draft_timeframe.clone())
+    let theme = &app.theme;
+    let label_active = theme.fg(theme.accent).add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    let label_inactive = theme.fg(theme.accent).add_modifier(Modifier::BOLD);
+    let placeholder_style = theme.fg(theme.faint).add_modifier(Modifier::ITALIC);
+
+    // text fields scroll horizontally inside the box (borders take 2 columns)
+    let inner_width = box_width.saturating_sub(2) as usize;
+    let field = |label: &'static str, input: &'a TextInput, placeholder: &'static str, which: InsertField| {
+        let active = app.insert_field == which;
+        let mut spans = vec![Span::styled(label, if active { label_active } else { label_inactive })];
+        if input.is_empty() && !active {
+            spans.push(Span::styled(placeholder, placeholder_style));
+        } else {
+            let visible = input.visible(inner_width.saturating_sub(label.width()));
+            spans.push(Span::raw(visible.before));
+            spans.push(Span::raw(if active { caret } else { "" }));
+            spans.push(Span::raw(visible.after));
+            if input.is_empty() {
+                spans.push(Span::styled(placeholder, placeholder_style));
+            }
+        }
+        TextLine::from(spans)
     };
-    let tf_line = TextLine::from(vec![
-        Span::styled("Time:  ", tf_label_style),
-        tf_span,
-        if matches!(app.insert_field, InsertField::Time) { Span::raw(caret) } else { Span::raw("") },
-    ]);
+    let title_line = field("Title: ", &app.draft_title, "<type a title>", InsertField::Title);
+    let notes_line = field("Notes: ", &app.draft_notes, "<add optional notes>", InsertField::Notes);
+    let tf_line = field("Time:  ", &app.draft_timeframe, "<e.g. Today 3–5pm | 2025-09-10 09:00>", InsertField::Time);
+    let rep_line = field(
+        "Repeat: ",
+        &app.draft_recurrence,
+        "<e.g. daily | every 2w | mon,fri | monthly 1 | after 3d>",
+        InsertField::Repeat,
+    );
 
     // Priority (focusable; arrows adjust)
     let prio_label_style = if matches!(app.insert_field, InsertField::Priority) { label_active } else { label_inactive };
@@ -221,30 +478,270 @@ fn draw_insert_overlay(frame: &mut Frame, app: &App, content_area: Rect) {
         prio_spans.push(Span::styled(
             if active_num { format!("[{}]", n) } else { format!(" {} ", n) },
             if active_num {
-                let mut s = Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD);
+                let mut s = theme.fg(theme.highlight).add_modifier(Modifier::BOLD);
                 if matches!(app.insert_field, InsertField::Priority) {
                     s = s.add_modifier(Modifier::REVERSED);
                 }
                 s
             } else {
-                Style::default().fg(Color::DarkGray)
+                theme.fg(theme.faint)
             },
         ));
         if n != 5 { prio_spans.push(Span::raw(" ")); }
     }
     prio_spans.push(Span::raw("   "));
-    prio_spans.push(Span::styled("[←/→ adjust] [Tab switch] [Enter add] [Esc cancel]", Style::default().fg(Color::Gray)));
+    let enter = if app.editing.is_some() { "save" } else { "add" };
+    prio_spans.push(Span::styled(format!("[←/→ adjust] [Tab/⇧Tab switch] [Enter {enter}] [Esc cancel] [F1 keys]"), theme.fg(theme.muted)));
     let prio_line = TextLine::from(prio_spans);
 
+    let parent_title = app
+        .insert_parent
+        .as_deref()
+        .and_then(|id| app.list.find_index_by_id(id))
+        .map(|i| app.list.items[i].title.as_str());
+    let overlay_title = match parent_title {
+        _ if app.editing.is_some() => "Edit Task".to_string(),
+        Some(p) => format!("Add Subtask of \"{p}\""),
+        None => "Add Task".to_string(),
+    };
+
+    // each number takes 3 columns plus a space, after the label on the fifth line
+    let prio_x = rect.x + 1 + "Priority: ".width() as u16;
+    app.hits.borrow_mut().priorities =
+        (0..5).map(|i| Rect::new(prio_x + i * 4, rect.y + 5, 3, 1).intersection(rect)).collect();
+
     frame.render_widget(Clear, rect);
-    let panel = Paragraph::new(vec![title_line, notes_line, tf_line, prio_line])
+    let panel = Paragraph::new(vec![title_line, notes_line, tf_line, rep_line, prio_line])
         .wrap(Wrap { trim: false })
-        .block(Block::default().borders(Borders::ALL).title("Add Task"));
+        .block(Block::default().borders(Borders::ALL).title(overlay_title));
+    frame.render_widget(panel, rect);
+}
+
+// =================== HELP OVERLAY ========================================
+fn draw_help(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
+    let rect = Rect {
+        x: area.x + area.width.saturating_sub(72) / 2,
+        y: area.y + 1,
+        width: 72.min(area.width),
+        height: area.height.saturating_sub(2),
+    };
+    let entries = help::matching(help::entries(app), &app.help.query);
+    let keys_w = entries.iter().map(|e| e.keys.width()).max().unwrap_or(0);
+
+    let mut lines: Vec<TextLine> = Vec::new();
+    for (i, e) in entries.iter().enumerate() {
+        if i == 0 || entries[i - 1].group != e.group {
+            if i > 0 {
+                lines.push(TextLine::default());
+            }
+            lines.push(TextLine::from(Span::styled(e.group, theme.fg(theme.accent).add_modifier(Modifier::BOLD))));
+        }
+        let pad = " ".repeat(keys_w - e.keys.width());
+        lines.push(TextLine::from(vec![
+            Span::styled(format!("  {}{pad}", e.keys), theme.fg(theme.tag)),
+            Span::raw(format!("  {}", e.label)),
+        ]));
+    }
+    if entries.is_empty() {
+        lines.push(TextLine::from(Span::styled(format!("No keys match \"{}\"", app.help.query), theme.fg(theme.faint))));
+    }
+
+    let block = Block::default().borders(Borders::ALL).title(format!("Keys · {}", help::context(app)));
+    let inner = block.inner(rect);
+    frame.render_widget(Clear, rect);
+    frame.render_widget(block, rect);
+    let [search_area, body_area] = Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(inner);
+
+    let total = lines.len() as u16;
+    app.help.scroll_max.set(total.saturating_sub(body_area.height));
+    let scroll = app.help.scroll.min(app.help.scroll_max.get());
+    let mut search = vec![Span::styled("Search: ", theme.fg(theme.accent).add_modifier(Modifier::BOLD))];
+    if app.help.query.is_empty() {
+        search.push(Span::styled("type to filter · ↑↓ PgUp/PgDn scroll · Esc close", theme.fg(theme.muted)));
+    } else {
+        search.push(Span::raw(format!("{}▏", app.help.query)));
+    }
+    if total > body_area.height {
+        search.push(Span::styled(
+            format!("  {}-{} of {total}", scroll + 1, (scroll + body_area.height).min(total)),
+            theme.fg(theme.faint),
+        ));
+    }
+    frame.render_widget(Paragraph::new(TextLine::from(search)), search_area);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), body_area);
+}
+
+// =================== DELETE CONFIRMATION =================================
+fn draw_confirm_delete(frame: &mut Frame, app: &App, content_area: Rect) {
+    let Some(idx) = app.pending_delete.as_deref().and_then(|id| app.list.find_index_by_id(id)) else {
+        return;
+    };
+    let t = &app.list.items[idx];
+    let n = app.list.descendants(idx).len();
+
+    let box_height = 5;
+    let rect = Rect {
+        x: content_area.x + 2,
+        y: content_area.y + content_area.height.saturating_sub(box_height + 1),
+        width: content_area.width.saturating_sub(4),
+        height: box_height.min(content_area.height),
+    };
+    let key = app.theme.fg(app.theme.accent).add_modifier(Modifier::BOLD);
+    let lines = vec![
+        TextLine::from(format!("\"{}\" has {n} subtask(s).", t.title)),
+        TextLine::from(vec![
+            Span::styled("[a]", key),
+            Span::raw(" delete all   "),
+            Span::styled("[r]", key),
+            Span::raw(" keep subtasks (move them up)   "),
+            Span::styled("[Esc]", key),
+            Span::raw(" cancel"),
+        ]),
+    ];
+    frame.render_widget(Clear, rect);
+    let panel = Paragraph::new(lines)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title("Delete Task"));
+    frame.render_widget(panel, rect);
+}
+
+fn draw_confirm_bulk_delete(frame: &mut Frame, app: &App, content_area: Rect) {
+    let n = app.marked_ids().len();
+    let box_height = 5;
+    let rect = Rect {
+        x: content_area.x + 2,
+        y: content_area.y + content_area.height.saturating_sub(box_height + 1),
+        width: content_area.width.saturating_sub(4),
+        height: box_height.min(content_area.height),
+    };
+    let key = app.theme.fg(app.theme.accent).add_modifier(Modifier::BOLD);
+    let lines = vec![
+        TextLine::from(format!("Move {n} marked task(s) to the trash? Unmarked subtasks move up.")),
+        TextLine::from(vec![
+            Span::styled("[y]", key),
+            Span::raw(" delete   "),
+            Span::styled("[Esc]", key),
+            Span::raw(" cancel"),
+        ]),
+    ];
+    frame.render_widget(Clear, rect);
+    let panel = Paragraph::new(lines)
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title(format!("Delete {n} Tasks")));
     frame.render_widget(panel, rect);
 }
 
+// =================== STATUS PICKER =======================================
+fn draw_status_picker(frame: &mut Frame, app: &App, content_area: Rect) {
+    let Some(idx) = app.selected_index() else { return };
+    let current = app.list.items[idx].status;
+    let theme = &app.theme;
+
+    // one line per status plus a hint line => 6 + 1 + borders
+    let box_height = Status::ALL.len() as u16 + 3;
+    let width = 36.min(content_area.width);
+    let rect = Rect {
+        x: content_area.x + content_area.width.saturating_sub(width) / 2,
+        y: content_area.y + content_area.height.saturating_sub(box_height) / 2,
+        width,
+        height: box_height.min(content_area.height),
+    };
+    let mut lines: Vec<TextLine> = Status::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, &s)| {
+            let mut style = if s == current || current.can_transition_to(s) {
+                Style::default()
+            } else {
+                // not reachable from the current status
+                theme.fg(theme.faint)
+            };
+            if i == app.status_pick {
+                style = style.patch(theme.selected());
+            }
+            let here = if s == current { "  (current)" } else { "" };
+            TextLine::from(Span::styled(format!("{} {} {s}{here}", i + 1, s.mark()), style))
+        })
+        .collect();
+    lines.push(TextLine::from(Span::styled("1-6 / ↑↓ Enter pick · Esc cancel", theme.fg(theme.muted))));
+    frame.render_widget(Clear, rect);
+    let panel = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(format!("Status: {}", app.list.items[idx].title)));
+    frame.render_widget(panel, rect);
+}
+
+// =================== BULK TAG PROMPT =====================================
+fn draw_bulk_tag(frame: &mut Frame, app: &App, content_area: Rect) {
+    let box_height = 4;
+    let rect = Rect {
+        x: content_area.x + 2,
+        y: content_area.y + content_area.height.saturating_sub(box_height + 1),
+        width: content_area.width.saturating_sub(4),
+        height: box_height.min(content_area.height),
+    };
+    let lines = vec![
+        TextLine::from(vec![
+            Span::styled("Tag: ", app.theme.fg(app.theme.accent).add_modifier(Modifier::BOLD)),
+            Span::raw(format!("{}▏", app.draft_tag)),
+        ]),
+        TextLine::from(Span::styled("+tag adds, -tag removes · Enter apply · Esc cancel", app.theme.fg(app.theme.muted))),
+    ];
+    frame.render_widget(Clear, rect);
+    let title = format!("Tag {} marked task(s)", app.marked_ids().len());
+    let panel = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    frame.render_widget(panel, rect);
+}
+
+// =================== TAB 3: TRASH ==========================================
+fn draw_trash(frame: &mut Frame, app: &App, area: Rect) {
+    let [table_area, footer] = Layout::vertical([Constraint::Min(1), Constraint::Length(4)]).areas(area);
+    let now = Local::now().timestamp();
+    let rows = trash::rows(app);
+
+    // keep the selected row in view
+    let viewport = table_area.height.saturating_sub(2) as usize;
+    let start = (app.trash_selected + 1).saturating_sub(viewport.max(1));
+    let table_rows: Vec<Row> = rows
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(viewport)
+        .map(|(pos, &i)| {
+            let t = &app.list.trash[i];
+            let parent = t.parent_id.as_deref().and_then(|p| {
+                let in_trash = app.list.trash.iter().find(|x| x.id == p);
+                in_trash.map(|x| format!("  (under \"{}\")", x.title))
+            });
+            let style = if pos == app.trash_selected { app.theme.selected() } else { app.theme.fg(app.theme.muted) };
+            Row::new(vec![
+                Cell::from(format!("{}  [P{}] {}{}", t.short_id(), t.priority, t.title, parent.unwrap_or_default())),
+                Cell::from(t.deleted_at.map(|d| due::ago(d, now)).unwrap_or_default()),
+            ])
+            .style(style)
+        })
+        .collect();
+    let table = Table::new(table_rows, [Constraint::Percentage(80), Constraint::Percentage(20)])
+        .block(Block::default().borders(Borders::ALL).title(format!("Trash · {} deleted", rows.len())))
+        .column_spacing(2);
+    frame.render_widget(table, table_area);
+    let mut hits = app.hits.borrow_mut();
+    hits.table = table_area.inner(Margin::new(1, 1));
+    hits.table_first_row = start;
+    drop(hits);
+
+    let help = Paragraph::new(vec![
+        TextLine::from(app.keymap.help_line(TRASH_HELP)),
+        TextLine::from(status_text(app)),
+    ])
+    .wrap(Wrap { trim: true })
+    .block(Block::default().borders(Borders::ALL).title("Status"));
+    frame.render_widget(help, footer);
+}
+
 // =================== TAB 1: DASH ===========================================
 fn draw_dash(frame: &mut Frame, app: &App, area: Rect) {
+    let theme = &app.theme;
     let cols = if app.show_chart {
         vec![Constraint::Percentage(45), Constraint::Percentage(55)]
     } else {
@@ -259,32 +756,63 @@ fn draw_dash(frame: &mut Frame, app: &App, area: Rect) {
     {
         let left = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(7), Constraint::Length(3), Constraint::Min(1)].as_ref())
+            .constraints([Constraint::Length(7), Constraint::Length(3), Constraint::Length(8), Constraint::Min(1)].as_ref())
             .split(chunks[0]);
 
         let pct = app.percent_done();
-        let anim = app.progress;
         let label = format!("Done: {:>5.1}%", pct * 100.0);
         let gauge = Gauge::default()
             .block(Block::default().borders(Borders::ALL).title("Completion"))
-            .gauge_style(Style::default().fg(Color::Magenta).bg(Color::Black).add_modifier(Modifier::BOLD))
+            .gauge_style(theme.fg(theme.highlight).bg(theme.color(theme.contrast)).add_modifier(Modifier::BOLD))
             .label(label)
-            .ratio(((pct * 0.85) + (anim * 0.15)).clamp(0.0, 1.0));
+            .ratio(pct.clamp(0.0, 1.0));
         frame.render_widget(gauge, left[0]);
 
+        // reduced motion: the share of open tasks in progress instead of a pulse
+        let focus = if app.reduced_motion {
+            let open = app.list.items.iter().filter(|t| !t.is_closed()).count();
+            let started = app.list.count_status(Status::InProgress);
+            if open == 0 { 0.0 } else { started as f64 / open as f64 }
+        } else {
+            0.5 + 0.5 * app.pulse.sin()
+        };
         let lg = LineGauge::default()
             .block(Block::default().borders(Borders::ALL).title("Focus"))
-            .filled_style(Style::default().fg(Color::Cyan))
+            .filled_style(theme.fg(theme.tag))
             .line_set(if app.enhanced_graphics { symbols::line::THICK } else { symbols::line::NORMAL })
-            .ratio((0.5 + 0.5 * app.pulse.sin()).clamp(0.0, 1.0));
+            .ratio(focus.clamp(0.0, 1.0));
         frame.render_widget(lg, left[1]);
 
+        // reduced motion: real completions per day instead of the animated wave
+        let (activity, spark) = if app.reduced_motion {
+            let days = left[3].width.saturating_sub(2).max(1) as usize;
+            ("Activity · done per day", app.completions_per_day(days, Local::now().timestamp()))
+        } else {
+            ("Activity", app.spark_points.clone())
+        };
         let sp = ratatui::widgets::Sparkline::default()
-            .block(Block::default().borders(Borders::ALL).title("Activity"))
-            .style(Style::default().fg(Color::Green))
-            .data(&app.spark_points)
+            .block(Block::default().borders(Borders::ALL).title(activity))
+            .style(theme.fg(theme.success))
+            .data(&spark)
             .bar_set(if app.enhanced_graphics { symbols::bar::NINE_LEVELS } else { symbols::bar::THREE_LEVELS });
-        frame.render_widget(sp, left[2]);
+        frame.render_widget(sp, left[3]);
+
+        let statuses = app.counts_by_status();
+        let bars: Vec<Bar> = statuses
+            .iter()
+            .map(|(s, n)| Bar::default().label(TextLine::from(s.to_string())).value(*n))
+            .collect();
+        let by_status = BarChart::default()
+            .block(Block::default().borders(Borders::ALL).title("By Status"))
+            .data(BarGroup::default().bars(&bars))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_set(if app.enhanced_graphics { symbols::bar::NINE_LEVELS } else { symbols::bar::THREE_LEVELS })
+            .value_style(theme.on(theme.highlight))
+            .label_style(theme.fg(theme.accent))
+            .bar_style(theme.fg(theme.highlight));
+        frame.render_widget(by_status, left[2]);
     }
 
     if app.show_chart {
@@ -297,10 +825,32 @@ fn draw_dash(frame: &mut Frame, app: &App, area: Rect) {
             .bar_width(4)
             .bar_gap(1)
             .bar_set(if app.enhanced_graphics { symbols::bar::NINE_LEVELS } else { symbols::bar::THREE_LEVELS })
-            .value_style(Style::default().fg(Color::Black).bg(Color::Green))
-            .label_style(Style::default().fg(Color::Yellow))
-            .bar_style(Style::default().fg(Color::Green));
-        frame.render_widget(bc, chunks[1]);
+            .value_style(theme.on(theme.success))
+            .label_style(theme.fg(theme.accent))
+            .bar_style(theme.fg(theme.success));
+
+        let charts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(chunks[1]);
+        frame.render_widget(bc, charts[0]);
+
+        let projects = app.counts_by_project();
+        let bars: Vec<Bar> = projects
+            .iter()
+            .map(|(name, n)| Bar::default().label(TextLine::from(name.as_str())).value(*n))
+            .collect();
+        let by_project = BarChart::default()
+            .block(Block::default().borders(Borders::ALL).title("By Project"))
+            .data(BarGroup::default().bars(&bars))
+            .direction(Direction::Horizontal)
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_set(if app.enhanced_graphics { symbols::bar::NINE_LEVELS } else { symbols::bar::THREE_LEVELS })
+            .value_style(theme.on(theme.project))
+            .label_style(theme.fg(theme.accent))
+            .bar_style(theme.fg(theme.project));
+        frame.render_widget(by_project, charts[1]);
     }
 }
 
@@ -312,6 +862,7 @@ fn draw_world(frame: &mut Frame, app: &App, area: Rect) {
         .split(area);
 
     // small selector header inside the tab
+    let theme = &app.theme;
     let view_titles: Vec<TextLine> = ["World", "NYC"]
         .iter()
         .enumerate()
@@ -320,9 +871,9 @@ fn draw_world(frame: &mut Frame, app: &App, area: Rect) {
             TextLine::from(Span::styled(
                 *t,
                 if active {
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                    theme.fg(theme.accent).add_modifier(Modifier::BOLD)
                 } else {
-                    Style::default().fg(Color::Green)
+                    theme.fg(theme.tab)
                 },
             ))
         })
@@ -332,8 +883,10 @@ fn draw_world(frame: &mut Frame, app: &App, area: Rect) {
     let selector = Tabs::new(view_titles)
         .block(Block::default().borders(Borders::ALL).title("Map View — press m"))
         .select(selected_idx)
-        .highlight_style(Style::default().fg(Color::Yellow));
+        .highlight_style(theme.fg(theme.accent));
     frame.render_widget(selector, sections[0]);
+    let views = tab_rects(sections[0], &["World", "NYC"]);
+    app.hits.borrow_mut().map_views = [MapView::World, MapView::NYC].into_iter().zip(views).collect();
 
     match app.map_view {
         MapView::World => draw_world_global(frame, app, sections[1]),
@@ -342,6 +895,7 @@ fn draw_world(frame: &mut Frame, app: &App, area: Rect) {
 }
 
 fn draw_world_global(frame: &mut Frame, app: &App, area: Rect) {
+    let (map, route, label) = (app.theme.color(app.theme.map), app.theme.color(app.theme.muted), app.theme.fg(app.theme.map));
     let nyc = (40.71_f64, -74.00_f64);
     let sgp = (1.35_f64, 103.86_f64);
     let par = (48.85_f64, 2.35_f64);
@@ -358,19 +912,22 @@ fn draw_world_global(frame: &mut Frame, app: &App, area: Rect) {
         .marker(if app.enhanced_graphics { symbols::Marker::Braille } else { symbols::Marker::Dot })
         .paint(|ctx| {
             ctx.draw(&Map {
-                color: Color::White,
+                color: map,
                 resolution: if app.enhanced_graphics { MapResolution::High } else { MapResolution::Low },
             });
 
-            ctx.draw(&CanvasLine { x1: nyc.1, y1: nyc.0, x2: par.1, y2: par.0, color: Color::Gray });
-            ctx.draw(&CanvasLine { x1: nyc.1, y1: nyc.0, x2: sgp.1, y2: sgp.0, color: Color::Gray });
-            ctx.print(moving_lon, moving_lat, Span::styled("•", Style::default().fg(Color::White)));
+            ctx.draw(&CanvasLine { x1: nyc.1, y1: nyc.0, x2: par.1, y2: par.0, color: route });
+            ctx.draw(&CanvasLine { x1: nyc.1, y1: nyc.0, x2: sgp.1, y2: sgp.0, color: route });
+            if !app.reduced_motion {
+                ctx.print(moving_lon, moving_lat, Span::styled("•", label));
+            }
         });
 
     frame.render_widget(canvas, area);
 }
 
 fn draw_world_nyc(frame: &mut Frame, app: &App, area: Rect) {
+    let (map, label) = (app.theme.color(app.theme.map), app.theme.fg(app.theme.map));
     // If we loaded real NYC paths (from JSON or GeoJSON), draw them neutrally.
     if let (Some(paths), Some((lon_minmax, lat_minmax))) = (&app.nyc_paths, &app.nyc_bbox) {
         let x_bounds = *lon_minmax;
@@ -387,22 +944,22 @@ fn draw_world_nyc(frame: &mut Frame, app: &App, area: Rect) {
                         // draw ring as series of short white line segments
                         for seg in ring.windows(2) {
                             let a = seg[0]; let b = seg[1];
-                            ctx.draw(&CanvasLine { x1: a[0], y1: a[1], x2: b[0], y2: b[1], color: Color::White });
+                            ctx.draw(&CanvasLine { x1: a[0], y1: a[1], x2: b[0], y2: b[1], color: map });
                         }
                         // close the ring
                         if ring.len() > 2 {
                             let a = ring[ring.len()-1]; let b = ring[0];
-                            ctx.draw(&CanvasLine { x1: a[0], y1: a[1], x2: b[0], y2: b[1], color: Color::White });
+                            ctx.draw(&CanvasLine { x1: a[0], y1: a[1], x2: b[0], y2: b[1], color: map });
                         }
                     }
                 }
 
                 // Neutral labels (adjusted positions)
-                ctx.print(-74.01, 40.82, Span::styled("Manhattan",     Style::default().fg(Color::White).add_modifier(Modifier::BOLD))); // down & left
-                ctx.print(-73.88, 40.85, Span::styled("Bronx",         Style::default().fg(Color::White)));                                 // to the right
-                ctx.print(-73.84, 40.70, Span::styled("Queens",        Style::default().fg(Color::White)));
-                ctx.print(-73.97, 40.64, Span::styled("Brooklyn",      Style::default().fg(Color::White)));                                 // more down
-                ctx.print(-74.20, 40.60, Span::styled("Staten Island", Style::default().fg(Color::White)));
+                ctx.print(-74.01, 40.82, Span::styled("Manhattan",     label.add_modifier(Modifier::BOLD))); // down & left
+                ctx.print(-73.88, 40.85, Span::styled("Bronx",         label));                            // to the right
+                ctx.print(-73.84, 40.70, Span::styled("Queens",        label));
+                ctx.print(-73.97, 40.64, Span::styled("Brooklyn",      label));                            // more down
+                ctx.print(-74.20, 40.60, Span::styled("Staten Island", label));
             });
 
         frame.render_widget(canvas, area);
@@ -439,16 +996,16 @@ fn draw_world_nyc(frame: &mut Frame, app: &App, area: Rect) {
             for ring in outlines {
                 for seg in ring.windows(2) {
                     let (a, b) = (seg[0], seg[1]);
-                    ctx.draw(&CanvasLine { x1: a.0, y1: a.1, x2: b.0, y2: b.1, color: Color::White });
+                    ctx.draw(&CanvasLine { x1: a.0, y1: a.1, x2: b.0, y2: b.1, color: map });
                 }
             }
 
             // Neutral labels (adjusted positions)
-            ctx.print(-74.01, 40.82, Span::styled("Manhattan",     Style::default().fg(Color::White).add_modifier(Modifier::BOLD))); // down & left
-            ctx.print(-73.90, 40.88, Span::styled("Bronx",         Style::default().fg(Color::White)));                                 // to the right
-            ctx.print(-73.93, 40.70, Span::styled("Queens",        Style::default().fg(Color::White)));
-            ctx.print(-74.03, 40.64, Span::styled("Brooklyn",      Style::default().fg(Color::White)));                                 // more down
-            ctx.print(-74.20, 40.60, Span::styled("Staten Island", Style::default().fg(Color::White)));
+            ctx.print(-74.01, 40.82, Span::styled("Manhattan",     label.add_modifier(Modifier::BOLD))); // down & left
+            ctx.print(-73.90, 40.88, Span::styled("Bronx",         label));                            // to the right
+            ctx.print(-73.93, 40.70, Span::styled("Queens",        label));
+            ctx.print(-74.03, 40.64, Span::styled("Brooklyn",      label));                            // more down
+            ctx.print(-74.20, 40.60, Span::styled("Staten Island", label));
         });
 
     frame.render_widget(canvas, area);
@@ -0,0 +1,339 @@
+use std::io::Write;
+
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    due,
+    task::{Task, SHORT_ID_LEN},
+    todolist::TodoList,
+};
+
+/// Output format for `todo list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ListFormat {
+    /// Aligned columns for humans
+    #[default]
+    Table,
+    /// The data file representation (`{"items": [...]}`)
+    Json,
+    /// One JSON task object per line
+    Ndjson,
+    Csv,
+    Tsv,
+    /// Graphviz dependency graph (edges point from blocker to blocked task)
+    Dot,
+}
+
+/// Task fields selectable with `--fields`; names match the serde keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Field {
+    Id,
+    Title,
+    Notes,
+    Timeframe,
+    Due,
+    #[value(name = "due_end")]
+    DueEnd,
+    Priority,
+    Order,
+    Tags,
+    Project,
+    #[value(name = "parent_id")]
+    ParentId,
+    #[value(name = "blocked_by")]
+    BlockedBy,
+    Recurrence,
+    Status,
+    #[value(name = "created_at")]
+    CreatedAt,
+    #[value(name = "started_at")]
+    StartedAt,
+    #[value(name = "completed_at")]
+    CompletedAt,
+    #[value(name = "updated_at")]
+    UpdatedAt,
+}
+
+impl Field {
+    pub const ALL: &'static [Field] = &[
+        Field::Id,
+        Field::Title,
+        Field::Notes,
+        Field::Timeframe,
+        Field::Due,
+        Field::DueEnd,
+        Field::Priority,
+        Field::Order,
+        Field::Tags,
+        Field::Project,
+        Field::ParentId,
+        Field::BlockedBy,
+        Field::Recurrence,
+        Field::Status,
+        Field::CreatedAt,
+        Field::StartedAt,
+        Field::CompletedAt,
+        Field::UpdatedAt,
+    ];
+
+    const TABLE_DEFAULT: &'static [Field] = &[
+        Field::Id,
+        Field::Status,
+        Field::Priority,
+        Field::Title,
+        Field::Project,
+        Field::Tags,
+        Field::Timeframe,
+        Field::Due,
+        Field::Recurrence,
+    ];
+
+    /// Key of this field in the serialized task
+    pub fn key(self) -> &'static str {
+        match self {
+            Field::Id => "id",
+            Field::Title => "title",
+            Field::Notes => "notes",
+            Field::Timeframe => "timeframe",
+            Field::Due => "due",
+            Field::DueEnd => "due_end",
+            Field::Priority => "priority",
+            Field::Order => "order",
+            Field::Tags => "tags",
+            Field::Project => "project",
+            Field::ParentId => "parent_id",
+            Field::BlockedBy => "blocked_by",
+            Field::Recurrence => "recurrence",
+            Field::Status => "status",
+            Field::CreatedAt => "created_at",
+            Field::StartedAt => "started_at",
+            Field::CompletedAt => "completed_at",
+            Field::UpdatedAt => "updated_at",
+        }
+    }
+}
+
+/// Write `list` to `out` in `format`. An empty `fields` selects the format's defaults.
+pub fn write_list(list: &TodoList, format: ListFormat, fields: &[Field], out: &mut impl Write) -> Result<()> {
+    match format {
+        ListFormat::Table => write_table(list, pick(fields, Field::TABLE_DEFAULT), out),
+        ListFormat::Json if fields.is_empty() => {
+            serde_json::to_writer_pretty(&mut *out, list)?;
+            writeln!(out)?;
+            Ok(())
+        }
+        ListFormat::Json => {
+            let items = list.items.iter().map(|t| project(t, fields)).collect::<Result<Vec<_>>>()?;
+            let mut doc = Map::new();
+            doc.insert("items".into(), Value::Array(items));
+            serde_json::to_writer_pretty(&mut *out, &doc)?;
+            writeln!(out)?;
+            Ok(())
+        }
+        ListFormat::Ndjson => {
+            for t in &list.items {
+                if fields.is_empty() {
+                    serde_json::to_writer(&mut *out, t)?;
+                } else {
+                    serde_json::to_writer(&mut *out, &project(t, fields)?)?;
+                }
+                writeln!(out)?;
+            }
+            Ok(())
+        }
+        ListFormat::Csv => write_delimited(&list.items, pick(fields, Field::ALL), ',', csv_escape, out),
+        ListFormat::Tsv => write_delimited(&list.items, pick(fields, Field::ALL), '\t', tsv_escape, out),
+        ListFormat::Dot => write_dot(list, out),
+    }
+}
+
+fn pick<'a>(fields: &'a [Field], default: &'a [Field]) -> &'a [Field] {
+    if fields.is_empty() { default } else { fields }
+}
+
+/// Serialized task restricted to `fields`, in the requested order
+fn project(t: &Task, fields: &[Field]) -> Result<Value> {
+    let mut full = serialized(t)?;
+    let mut out = Map::new();
+    for f in fields {
+        out.insert(f.key().into(), full.remove(f.key()).unwrap_or(Value::Null));
+    }
+    Ok(Value::Object(out))
+}
+
+fn serialized(t: &Task) -> Result<Map<String, Value>> {
+    match serde_json::to_value(t)? {
+        Value::Object(map) => Ok(map),
+        _ => unreachable!("tasks serialize to objects"),
+    }
+}
+
+/// Plain-text rendering of one field, shared by the table and delimited formats
+fn cell(task: &Map<String, Value>, field: Field) -> String {
+    task.get(field.key()).map(plain).unwrap_or_default()
+}
+
+fn plain(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(plain).collect::<Vec<_>>().join(","),
+        other => other.to_string(),
+    }
+}
+
+/// Tasks in tree order with subtask titles indented
+fn write_table(list: &TodoList, fields: &[Field], out: &mut impl Write) -> Result<()> {
+    if list.is_empty() {
+        writeln!(out, "No tasks.")?;
+        return Ok(());
+    }
+
+    let now = Local::now().timestamp();
+    let mut rows: Vec<Vec<String>> = vec![fields.iter().map(|f| f.key().to_uppercase()).collect()];
+    for (idx, depth) in list.tree_order() {
+        let t = &list.items[idx];
+        let map = serialized(t)?;
+        let row = fields
+            .iter()
+            .map(|&f| match f {
+                Field::Id => t.short_id().to_string(),
+                Field::Status => t.status.mark().to_string(),
+                Field::Priority => format!("P{}", t.priority),
+                Field::Title => format!("{}{}", "  ".repeat(depth), t.title),
+                Field::ParentId => t.parent_id.as_deref().map(short).unwrap_or_default(),
+                Field::BlockedBy => t.blocked_by.iter().map(|b| short(b)).collect::<Vec<_>>().join(","),
+                Field::Tags => t.tags.iter().map(|tag| format!("+{tag}")).collect::<Vec<_>>().join(" "),
+                Field::Project => t.project.as_deref().map(|p| format!("@{p}")).unwrap_or_default(),
+                Field::Due => t.due.map(|d| due::relative(d, now)).unwrap_or_default(),
+                Field::DueEnd => t.due_end.map(local_time).unwrap_or_default(),
+                Field::CreatedAt => local_time(t.created_at),
+                Field::StartedAt => t.started_at.map(local_time).unwrap_or_default(),
+                Field::CompletedAt => t.completed_at.map(local_time).unwrap_or_default(),
+                Field::UpdatedAt => t.updated_at.map(local_time).unwrap_or_default(),
+                _ => cell(&map, f).replace(['\n', '\r'], " "),
+            })
+            .collect();
+        rows.push(row);
+    }
+
+    let widths: Vec<usize> = (0..fields.len())
+        .map(|c| rows.iter().map(|r| r[c].width()).max().unwrap_or(0))
+        .collect();
+    for row in &rows {
+        let mut line = String::new();
+        for (c, text) in row.iter().enumerate() {
+            line.push_str(text);
+            if c + 1 < row.len() {
+                line.push_str(&" ".repeat(widths[c] - text.width() + 2));
+            }
+        }
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
+
+fn short(id: &str) -> String {
+    id.chars().take(SHORT_ID_LEN).collect()
+}
+
+fn local_time(ts: i64) -> String {
+    Local
+        .timestamp_opt(ts, 0)
+        .single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn write_delimited(
+    items: &[Task],
+    fields: &[Field],
+    sep: char,
+    escape: fn(&str) -> String,
+    out: &mut impl Write,
+) -> Result<()> {
+    let header: Vec<&str> = fields.iter().map(|f| f.key()).collect();
+    writeln!(out, "{}", header.join(&sep.to_string()))?;
+    for t in items {
+        let map = serialized(t)?;
+        let row: Vec<String> = fields.iter().map(|&f| escape(&cell(&map, f))).collect();
+        writeln!(out, "{}", row.join(&sep.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Dependency graph for Graphviz: closed tasks are greyed, blocked ones dashed
+fn write_dot(list: &TodoList, out: &mut impl Write) -> Result<()> {
+    writeln!(out, "digraph todo {{")?;
+    writeln!(out, "  rankdir=LR;")?;
+    writeln!(out, "  node [shape=box, style=rounded];")?;
+    for (i, t) in list.items.iter().enumerate() {
+        let style = if t.is_closed() {
+            ", style=\"rounded,filled\", fillcolor=lightgrey"
+        } else if list.is_blocked(i) {
+            ", style=\"rounded,dashed\""
+        } else {
+            ""
+        };
+        writeln!(out, "  \"{}\" [label={}{style}];", t.short_id(), dot_string(&t.title))?;
+    }
+    for (i, t) in list.items.iter().enumerate() {
+        for b in list.blockers(i) {
+            writeln!(out, "  \"{}\" -> \"{}\";", list.items[b].short_id(), t.short_id())?;
+        }
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// RFC 4180 quoting: wrap in quotes when needed and double embedded quotes
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// TSV has no quoting, so escape the characters that would break a row
+fn tsv_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n").replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> TodoList {
+        let mut list = TodoList::default();
+        list.add("Buy milk, eggs", 2, Some("say \"hi\"".into()));
+        list.items[0].timeframe = Some("Today".into());
+        list
+    }
+
+    #[test]
+    fn json_round_trips_through_the_data_file_format() {
+        let list = sample();
+        let mut out = Vec::new();
+        write_list(&list, ListFormat::Json, &[], &mut out).unwrap();
+        let back: TodoList = serde_json::from_slice(&out).unwrap();
+        assert_eq!(serde_json::to_value(&back).unwrap(), serde_json::to_value(&list).unwrap());
+    }
+
+    #[test]
+    fn csv_quotes_and_respects_field_selection() {
+        let list = sample();
+        let mut out = Vec::new();
+        write_list(&list, ListFormat::Csv, &[Field::Title, Field::Notes, Field::Priority], &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text, "title,notes,priority\n\"Buy milk, eggs\",\"say \"\"hi\"\"\",2\n");
+    }
+}
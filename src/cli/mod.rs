@@ -0,0 +1,310 @@
+pub mod format;
+
+use std::{io::Write, path::Path};
+
+use anyhow::{bail, Result};
+
+use crate::{
+    due, persistence,
+    task::{parse_inline_tokens, unix_now, Task},
+    todolist::{TaskFilter, TodoList},
+    Cmd, TrashCmd,
+};
+
+/// Run a non-interactive subcommand against the data file at `path`.
+/// `Cmd::Tui` is handled by the binary and rejected here. Commands that change
+/// the list also purge tasks trashed more than `purge_after_days` ago.
+pub fn run(cmd: Cmd, path: &Path, purge_after_days: u32) -> Result<()> {
+    let mut list = persistence::load(path)?;
+    let mut history = persistence::load_history(path)?;
+    let mut out = std::io::stdout().lock();
+    let mut warn = std::io::stderr().lock();
+    let changed = match cmd {
+        Cmd::Undo | Cmd::Redo => {
+            let redo = matches!(cmd, Cmd::Redo);
+            let step = if redo { history.redo(&mut list) } else { history.undo(&mut list) };
+            let Some(label) = step else {
+                bail!("nothing to {}", if redo { "redo" } else { "undo" });
+            };
+            writeln!(out, "{}: {label}", if redo { "Redid" } else { "Undid" })?;
+            true
+        }
+        cmd => {
+            let label = describe(&cmd, &list);
+            let before = list.clone();
+            let changed = execute(cmd, &mut list, &mut out, &mut warn)?;
+            if changed {
+                history.record(label, before);
+                history.auto_purge(&mut list, purge_after_days);
+            }
+            changed
+        }
+    };
+    if changed {
+        persistence::save(path, &list)?;
+        persistence::save_history(path, &history)?;
+    }
+    Ok(())
+}
+
+/// Short description of a command for the undo history
+fn describe(cmd: &Cmd, list: &TodoList) -> String {
+    let title = |id: &str| match list.resolve_id_prefix(id) {
+        Ok(i) => format!("\"{}\"", list.items[i].title),
+        Err(_) => id.to_string(),
+    };
+    match cmd {
+        Cmd::Add { title, .. } => format!("Add \"{}\"", parse_inline_tokens(title).title),
+        Cmd::Done { id, .. } => format!("Toggle {}", title(id)),
+        Cmd::Status { id, status } => format!("Set {} to {status}", title(id)),
+        Cmd::Block { id, .. } => format!("Dependencies of {}", title(id)),
+        Cmd::Move { id, .. } => format!("Move {}", title(id)),
+        Cmd::Delete { id, .. } => format!("Delete {}", title(id)),
+        Cmd::Trash { action } => {
+            let trashed = |id: &str| match list.resolve_trash_prefix(id) {
+                Ok(i) => format!("\"{}\"", list.trash[i].title),
+                Err(_) => id.to_string(),
+            };
+            match action {
+                TrashCmd::Restore { id } => format!("Restore {}", trashed(id)),
+                TrashCmd::Purge { id: Some(id), .. } => format!("Purge {}", trashed(id)),
+                TrashCmd::Purge { .. } => "Empty trash".into(),
+                TrashCmd::List => String::new(),
+            }
+        }
+        _ => String::new(),
+    }
+}
+
+/// Apply `cmd` to `list`, writing human output to `out` and notes about the
+/// input to `warn`. Returns whether the list changed and needs saving.
+pub fn execute(cmd: Cmd, list: &mut TodoList, out: &mut impl Write, warn: &mut impl Write) -> Result<bool> {
+    match cmd {
+        Cmd::Add { title, priority, notes, timeframe, repeat, parent, blocked_by } => {
+            let parsed = parse_inline_tokens(&title);
+            if parsed.title.is_empty() {
+                bail!("title cannot be empty");
+            }
+            let parent_id = match parent {
+                Some(p) => Some(list.items[list.resolve_id_prefix(&p)?].id.clone()),
+                None => None,
+            };
+            let blockers = blocked_by.iter().map(|b| list.resolve_id_prefix(b)).collect::<Result<Vec<_>, _>>()?;
+            let task = list.add(&parsed.title, priority.unwrap_or(1), notes);
+            task.parent_id = parent_id;
+            task.tags = parsed.tags;
+            task.project = parsed.project;
+            task.set_timeframe(timeframe.filter(|tf| !tf.trim().is_empty()));
+            task.recurrence = repeat;
+            if task.timeframe.is_some() && task.due.is_none() {
+                writeln!(warn, "note: could not parse a due time from the timeframe")?;
+            }
+            let line = format!("Added {}: {}", task.short_id(), task.title);
+            let idx = list.len() - 1;
+            for b in blockers {
+                list.add_dependency(idx, b)?;
+            }
+            writeln!(out, "{line}")?;
+            Ok(true)
+        }
+        Cmd::List { format, fields, tags, project } => {
+            let filter = TaskFilter::new(tags, project);
+            let items = list.tree_order().into_iter().map(|(i, _)| &list.items[i]).filter(|t| filter.matches(t));
+            let view = TodoList { items: items.cloned().collect(), ..TodoList::default() };
+            format::write_list(&view, format, &fields, out)?;
+            Ok(false)
+        }
+        Cmd::Done { id, recursive, force } => {
+            let index = list.resolve_id_prefix(&id)?;
+            let blockers = list.pending_blockers(index);
+            if !list.items[index].is_done() && !blockers.is_empty() {
+                let names: Vec<String> = blockers
+                    .iter()
+                    .map(|&b| format!("{} {}", list.items[b].short_id(), list.items[b].title))
+                    .collect();
+                if !force {
+                    bail!("task is blocked by pending task(s): {} (use --force to complete anyway)", names.join(", "));
+                }
+                writeln!(warn, "warning: completing despite pending blockers: {}", names.join(", "))?;
+            }
+            let before = list.len();
+            // blockers were checked above
+            list.force_toggle_done_index(index);
+            if recursive {
+                list.cascade_status(index);
+            }
+            let t = &list.items[index];
+            let state = if t.is_done() { "done" } else { "pending" };
+            writeln!(out, "Marked {} {state}: {}", t.short_id(), t.title)?;
+            for next in &list.items[before..] {
+                let when = next.timeframe.as_deref().unwrap_or_default();
+                writeln!(out, "Next occurrence {}: {when}", next.short_id())?;
+            }
+            Ok(true)
+        }
+        Cmd::Status { id, status } => {
+            let index = list.resolve_id_prefix(&id)?;
+            let before = list.len();
+            list.set_status_index(index, status)?;
+            let t = &list.items[index];
+            writeln!(out, "{} is now {}: {}", t.short_id(), t.status.to_string().to_lowercase(), t.title)?;
+            for next in &list.items[before..] {
+                let when = next.timeframe.as_deref().unwrap_or_default();
+                writeln!(out, "Next occurrence {}: {when}", next.short_id())?;
+            }
+            Ok(true)
+        }
+        Cmd::Delete { id, with_children, reparent } => {
+            let index = list.resolve_id_prefix(&id)?;
+            let t = list.items[index].clone();
+            let subtasks = list.descendants(index).len();
+            if subtasks > 0 && with_children {
+                list.delete_tree(index);
+                writeln!(out, "Moved {} to the trash: {} and {subtasks} subtask(s)", t.short_id(), t.title)?;
+            } else if subtasks > 0 && !reparent {
+                bail!("task {} has {subtasks} subtask(s); pass --with-children or --reparent", t.short_id());
+            } else {
+                list.delete_index(index);
+                writeln!(out, "Moved {} to the trash: {}", t.short_id(), t.title)?;
+            }
+            Ok(true)
+        }
+        Cmd::Move { id, before } => {
+            let index = list.resolve_id_prefix(&id)?;
+            let target = list.resolve_id_prefix(&before)?;
+            if !list.move_before(index, target) {
+                bail!("can only move a task before another task with the same parent");
+            }
+            let (t, b) = (&list.items[index], &list.items[target]);
+            writeln!(out, "Moved {} before {}: {}", t.short_id(), b.short_id(), t.title)?;
+            Ok(true)
+        }
+        Cmd::Block { id, on, remove } => {
+            let index = list.resolve_id_prefix(&id)?;
+            let blockers = on.iter().map(|b| list.resolve_id_prefix(b)).collect::<Result<Vec<_>, _>>()?;
+            // removing links that aren't there changes nothing
+            let mut changed = !remove;
+            for b in blockers {
+                let (task, blocker) = (list.items[index].short_id().to_string(), list.items[b].short_id().to_string());
+                if remove {
+                    if list.remove_dependency(index, b) {
+                        writeln!(out, "{task} no longer waits for {blocker}")?;
+                        changed = true;
+                    }
+                } else {
+                    list.add_dependency(index, b)?;
+                    writeln!(out, "{task} now waits for {blocker}")?;
+                }
+            }
+            Ok(changed)
+        }
+        Cmd::Trash { action } => trash(action, list, out),
+        Cmd::Undo | Cmd::Redo => bail!("undo and redo need the history file; use `run`"),
+        Cmd::Tui => bail!("the TUI cannot be launched from here"),
+        Cmd::Config { .. } => bail!("config is handled by the binary"),
+    }
+}
+
+fn trash(action: TrashCmd, list: &mut TodoList, out: &mut impl Write) -> Result<bool> {
+    match action {
+        TrashCmd::List => {
+            if list.trash.is_empty() {
+                writeln!(out, "Trash is empty")?;
+            }
+            let now = unix_now();
+            let mut trashed: Vec<&Task> = list.trash.iter().collect();
+            trashed.sort_by_key(|t| std::cmp::Reverse(t.deleted_at));
+            for t in trashed {
+                let when = t.deleted_at.map(|d| due::ago(d, now)).unwrap_or_default();
+                writeln!(out, "{}  {:<8}  {}", t.short_id(), when, t.title)?;
+            }
+            Ok(false)
+        }
+        TrashCmd::Restore { id } => {
+            let index = list.resolve_trash_prefix(&id)?;
+            let t = list.trash[index].clone();
+            let n = list.restore(index);
+            writeln!(out, "Restored {}: {}{}", t.short_id(), t.title, subtasks_note(n))?;
+            Ok(true)
+        }
+        TrashCmd::Purge { id: Some(id), .. } => {
+            let index = list.resolve_trash_prefix(&id)?;
+            let t = list.trash[index].clone();
+            let n = list.purge(index);
+            writeln!(out, "Purged {}: {}{}", t.short_id(), t.title, subtasks_note(n))?;
+            Ok(true)
+        }
+        TrashCmd::Purge { .. } => {
+            let n = list.empty_trash();
+            writeln!(out, "Purged {n} task(s)")?;
+            Ok(n > 0)
+        }
+    }
+}
+
+fn subtasks_note(restored: usize) -> String {
+    match restored {
+        0 | 1 => String::new(),
+        n => format!(" and {} subtask(s)", n - 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_id_prefix_is_an_error() {
+        let mut list = TodoList::default();
+        list.add("Only task", 1, None);
+        let (mut out, mut warn) = (Vec::new(), Vec::new());
+        let done = Cmd::Done { id: "zzz".into(), recursive: false, force: false };
+        let err = execute(done, &mut list, &mut out, &mut warn).unwrap_err();
+        assert!(err.to_string().contains("no task id starts with 'zzz'"));
+        assert!(!list.items[0].is_done());
+    }
+
+    #[test]
+    fn unparsed_timeframe_note_goes_to_the_warning_writer() {
+        let mut list = TodoList::default();
+        let (mut out, mut warn) = (Vec::new(), Vec::new());
+        let add = Cmd::Add {
+            title: "Call back".into(),
+            priority: None,
+            notes: None,
+            timeframe: Some("whenever".into()),
+            repeat: None,
+            parent: None,
+            blocked_by: Vec::new(),
+        };
+        execute(add, &mut list, &mut out, &mut warn).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("Added "));
+        assert_eq!(String::from_utf8(warn).unwrap(), "note: could not parse a due time from the timeframe\n");
+    }
+
+    #[test]
+    fn forced_done_warns_about_blockers_on_the_warning_writer() {
+        let mut list = TodoList::default();
+        list.add("Ship", 1, None);
+        list.add("Blocker", 1, None);
+        list.add_dependency(0, 1).unwrap();
+        let mut warn = Vec::new();
+        let id = list.items[0].id.clone();
+        execute(Cmd::Done { id, recursive: false, force: true }, &mut list, &mut Vec::new(), &mut warn).unwrap();
+        assert!(String::from_utf8(warn).unwrap().starts_with("warning: completing despite pending blockers: "));
+        assert!(list.items[0].is_done());
+    }
+
+    #[test]
+    fn removing_a_missing_dependency_changes_nothing() {
+        let mut list = TodoList::default();
+        list.add("Ship", 1, None);
+        list.add("Build", 1, None);
+        let (id, on) = (list.items[0].id.clone(), list.items[1].id.clone());
+        let block = |remove| Cmd::Block { id: id.clone(), on: vec![on.clone()], remove };
+        assert!(!execute(block(true), &mut list, &mut Vec::new(), &mut Vec::new()).unwrap());
+        assert!(execute(block(false), &mut list, &mut Vec::new(), &mut Vec::new()).unwrap());
+        assert!(execute(block(true), &mut list, &mut Vec::new(), &mut Vec::new()).unwrap());
+        assert!(list.items[0].blocked_by.is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+//! User settings from `config.toml` in the config directory
+//! (`~/.config/todo-tui/config.toml` on Linux). Every key can be overridden
+//! by a `TODO_TUI_<KEY>` environment variable and then by `--set key=value`.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{app::Tab, persistence};
+
+/// Prefix of the environment overrides, e.g. `TODO_TUI_TICK_RATE_MS=250`
+pub const ENV_PREFIX: &str = "TODO_TUI_";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Where tasks are stored; the platform data directory when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_file: Option<PathBuf>,
+    /// How often the TUI animates, in milliseconds
+    pub tick_rate_ms: u64,
+    pub show_chart: bool,
+    pub enhanced_graphics: bool,
+    /// Turn off decorative animation and show exact values; the TUI then only redraws when something changes
+    pub reduced_motion: bool,
+    /// A built-in palette (dark, light, solarized, high-contrast, colorblind) or a user theme
+    pub theme: String,
+    /// Priority of new tasks when none is given
+    pub default_priority: i8,
+    /// Tabs shown in the TUI, in order
+    pub tabs: Vec<Tab>,
+    /// Count open parents by their subtasks' progress in the completion gauge,
+    /// rather than counting every task once
+    pub rollup_progress: bool,
+    /// Trashed tasks older than this many days are purged, as an undoable step,
+    /// when the TUI starts or a command changes the list; 0 keeps them
+    pub purge_after_days: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            data_file: None,
+            tick_rate_ms: 80,
+            show_chart: true,
+            enhanced_graphics: true,
+            reduced_motion: false,
+            theme: "dark".into(),
+            default_priority: 1,
+            tabs: Tab::ALL.to_vec(),
+            rollup_progress: true,
+            purge_after_days: 30,
+        }
+    }
+}
+
+/// Names of every setting, as written in the file
+fn known_keys() -> Vec<String> {
+    let mut keys: Vec<String> = toml::Table::try_from(Config::default()).map(|t| t.keys().cloned().collect()).unwrap_or_default();
+    keys.push("data_file".into());
+    keys.sort();
+    keys
+}
+
+impl Config {
+    /// Merge defaults, the file at `path` (if it exists), environment variables and
+    /// `overrides` (`key=value`, highest precedence)
+    pub fn load(path: &Path, overrides: &[String]) -> Result<Config> {
+        let mut table = if path.exists() {
+            let text = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+            toml::from_str::<toml::Table>(&text).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            toml::Table::new()
+        };
+        let keys = known_keys();
+        check_keys(table.keys(), &keys).with_context(|| format!("in {}", path.display()))?;
+
+        for key in &keys {
+            let var = format!("{ENV_PREFIX}{}", key.to_ascii_uppercase());
+            if let Ok(raw) = env::var(&var) {
+                table.insert(key.clone(), parse_value(&raw));
+            }
+        }
+        // a misspelled variable would otherwise be ignored without a word
+        let unknown_env: Vec<String> = env::vars()
+            .filter_map(|(var, _)| var.strip_prefix(ENV_PREFIX).map(str::to_ascii_lowercase))
+            .filter(|k| k != "config")
+            .collect();
+        check_keys(unknown_env.iter(), &keys).context("in the environment")?;
+
+        for o in overrides {
+            let Some((key, raw)) = o.split_once('=') else { bail!("--set expects key=value, got '{o}'") };
+            let key = key.trim();
+            check_keys(std::iter::once(&key.to_string()), &keys).context("in --set")?;
+            table.insert(key.to_string(), parse_value(raw.trim()));
+        }
+
+        let config: Config = table.try_into().context("invalid configuration")?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(1..=5).contains(&self.default_priority) {
+            bail!("default_priority must be between 1 and 5, got {}", self.default_priority);
+        }
+        if self.tick_rate_ms == 0 {
+            bail!("tick_rate_ms must be greater than 0");
+        }
+        if self.tabs.is_empty() {
+            bail!("tabs must list at least one of: {}", tab_names());
+        }
+        for (i, t) in self.tabs.iter().enumerate() {
+            if self.tabs[..i].contains(t) {
+                bail!("tab '{}' is listed twice", t.title().to_lowercase());
+            }
+        }
+        Ok(())
+    }
+
+    /// The data file, falling back to the platform data directory
+    pub fn data_path(&self) -> Result<PathBuf> {
+        match &self.data_file {
+            Some(p) => Ok(p.clone()),
+            None => persistence::default_path(),
+        }
+    }
+
+    /// The effective settings as TOML, as `todo config show` prints them,
+    /// with `data_file` resolved to the path in use
+    pub fn to_toml(&self) -> Result<String> {
+        let resolved = Config { data_file: Some(self.data_path()?), ..self.clone() };
+        Ok(toml::to_string(&resolved)?)
+    }
+}
+
+fn tab_names() -> String {
+    Tab::ALL.iter().map(|t| t.title().to_lowercase()).collect::<Vec<_>>().join(", ")
+}
+
+fn check_keys<'a>(found: impl Iterator<Item = &'a String>, known: &[String]) -> Result<()> {
+    for key in found {
+        if !known.contains(key) {
+            bail!("unknown setting '{key}'; valid settings are: {}", known.join(", "));
+        }
+    }
+    Ok(())
+}
+
+/// Read a value the way it would be written in the file, falling back to a
+/// plain string so `data_file=/tmp/x.json` works without quotes
+fn parse_value(raw: &str) -> toml::Value {
+    toml::from_str::<toml::Table>(&format!("v = {raw}"))
+        .ok()
+        .and_then(|mut t| t.remove("v"))
+        .unwrap_or_else(|| toml::Value::String(raw.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_and_overrides_merge_and_typos_are_errors() {
+        let dir = env::temp_dir().join(format!("todo-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "tick_rate_ms = 250\ntabs = [\"todos\", \"trash\"]\n").unwrap();
+
+        let config = Config::load(&path, &["show_chart=false".into(), "data_file=/tmp/t.json".into()]).unwrap();
+        assert_eq!(config.tick_rate_ms, 250);
+        assert_eq!(config.tabs, [Tab::Todos, Tab::Trash]);
+        assert!(!config.show_chart);
+        assert_eq!(config.data_file.as_deref(), Some(Path::new("/tmp/t.json")));
+
+        let err = Config::load(&path, &["tick_rat=5".into()]).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown setting 'tick_rat'"));
+        fs::write(&path, "default_priority = 9\n").unwrap();
+        assert!(format!("{:#}", Config::load(&path, &[]).unwrap_err()).contains("between 1 and 5"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
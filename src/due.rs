@@ -0,0 +1,242 @@
+//! Parsing of the free-text `timeframe` into structured due timestamps.
+//!
+//! Understands ISO dates (`2025-09-10`, `2025-09-10 09:00`), `today`/`tomorrow`,
+//! weekday names (`fri`, `next monday`), relative offsets (`in 3d`, `in 2h`)
+//! and clock times or ranges (`3pm`, `15:30`, `3–5pm`). Alternatives separated
+//! by `|` are tried left to right.
+
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Weekday,
+};
+
+/// Parsed due window, in unix seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DueRange {
+    pub start: i64,
+    pub end: Option<i64>,
+}
+
+/// Parse `text` relative to `now`. Returns `None` when nothing recognisable is found.
+pub fn parse_timeframe<Tz: TimeZone>(text: &str, now: &DateTime<Tz>) -> Option<DueRange> {
+    text.split('|').find_map(|part| parse_one(part, now))
+}
+
+fn parse_one<Tz: TimeZone>(text: &str, now: &DateTime<Tz>) -> Option<DueRange> {
+    let text = text.trim().to_lowercase().replace(['–', '—'], "-");
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Some(rest) = text.strip_prefix("in ") {
+        let start = now.clone().checked_add_signed(parse_offset(rest)?)?;
+        return Some(DueRange { start: start.timestamp(), end: None });
+    }
+
+    let today = now.naive_local().date();
+    let mut date: Option<NaiveDate> = None;
+    let mut time_tokens: Vec<&str> = Vec::new();
+    let mut next = false;
+
+    for tok in text.split_whitespace() {
+        if date.is_none() {
+            if tok == "next" {
+                next = true;
+                continue;
+            }
+            if let Some(d) = parse_day_word(tok, today, next) {
+                date = Some(d);
+                continue;
+            }
+            if let Some((d, t)) = parse_iso(tok) {
+                date = Some(d);
+                if let Some(t) = t {
+                    time_tokens.push(t);
+                }
+                continue;
+            }
+        }
+        if !matches!(tok, "at" | "@" | "by" | "on") {
+            time_tokens.push(tok);
+        }
+    }
+
+    let times = if time_tokens.is_empty() { None } else { Some(parse_time_range(&time_tokens.concat())?) };
+    let date = match (date, times) {
+        (Some(d), _) => d,
+        (None, Some(_)) => today,
+        (None, None) => return None,
+    };
+
+    let (start, end) = match times {
+        Some((s, e)) => (date.and_time(s), e.map(|e| date.and_time(e))),
+        // A bare date is due by the end of that day
+        None => (date.and_hms_opt(23, 59, 59)?, None),
+    };
+    let end = end.map(|e| if e <= start { e + Duration::days(1) } else { e });
+
+    let start = to_timestamp(&now.timezone(), start)?;
+    let end = end.and_then(|e| to_timestamp(&now.timezone(), e));
+    Some(DueRange { start, end })
+}
+
+fn to_timestamp<Tz: TimeZone>(tz: &Tz, naive: NaiveDateTime) -> Option<i64> {
+    tz.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp())
+}
+
+/// `3d`, `2h`, `30m`, `1w`, optionally with a spaced unit word (`3 days`);
+/// `None` when the offset is out of range
+fn parse_offset(text: &str) -> Option<Duration> {
+    let compact: String = text.split_whitespace().collect();
+    let split = compact.find(|c: char| !c.is_ascii_digit())?;
+    let (num, unit) = compact.split_at(split);
+    let n: i64 = num.parse().ok()?;
+    match unit {
+        "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(n),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(n),
+        "d" | "day" | "days" => Duration::try_days(n),
+        "w" | "wk" | "wks" | "week" | "weeks" => Duration::try_weeks(n),
+        _ => None,
+    }
+}
+
+fn parse_day_word(tok: &str, today: NaiveDate, next: bool) -> Option<NaiveDate> {
+    match tok {
+        "today" | "tonight" => return Some(today),
+        "tomorrow" | "tmr" | "tmrw" => return today.succ_opt(),
+        _ => {}
+    }
+    let wd = parse_weekday(tok)?;
+    let mut ahead = (7 + wd.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64) % 7;
+    if next && ahead == 0 {
+        ahead = 7;
+    }
+    Some(today + Duration::days(ahead))
+}
+
+pub(crate) fn parse_weekday(tok: &str) -> Option<Weekday> {
+    let wd = match tok {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "weds" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(wd)
+}
+
+/// `2025-09-10`, optionally followed by `T09:00`
+fn parse_iso(tok: &str) -> Option<(NaiveDate, Option<&str>)> {
+    let (date, time) = match tok.split_once('t') {
+        Some((d, t)) => (d, Some(t)),
+        None => (tok, None),
+    };
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some((date, time))
+}
+
+/// `3pm`, `15:30`, `3-5pm`, `09:00-10:30`
+fn parse_time_range(text: &str) -> Option<(NaiveTime, Option<NaiveTime>)> {
+    match text.split_once('-') {
+        Some((a, b)) => {
+            let (end, end_mer) = parse_clock(b)?;
+            let start = match parse_clock(a)? {
+                (t, Some(_)) => t,
+                // `3-5pm`: the start borrows the end's meridiem unless that would put it after the end
+                (t, None) if end_mer == Some(true) && t.hour() < 12 => {
+                    let pm = t + Duration::hours(12);
+                    if pm <= end { pm } else { t }
+                }
+                (t, None) => t,
+            };
+            Some((start, Some(end)))
+        }
+        None => parse_clock(text).map(|(t, _)| (t, None)),
+    }
+}
+
+/// Returns the time and whether an explicit meridiem was given (`Some(true)` for pm)
+fn parse_clock(text: &str) -> Option<(NaiveTime, Option<bool>)> {
+    let (body, pm) = if let Some(b) = text.strip_suffix("pm") {
+        (b, Some(true))
+    } else if let Some(b) = text.strip_suffix("am") {
+        (b, Some(false))
+    } else {
+        (text, None)
+    };
+    let (h, m) = match body.split_once(':') {
+        Some((h, m)) => (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?),
+        None => (body.parse::<u32>().ok()?, 0),
+    };
+    let h = match pm {
+        Some(_) if !(1..=12).contains(&h) => return None,
+        Some(true) => h % 12 + 12,
+        Some(false) => h % 12,
+        None => h,
+    };
+    NaiveTime::from_hms_opt(h, m, 0).map(|t| (t, pm))
+}
+
+/// Short relative text such as `in 2h`, `in 3d` or `3d overdue`
+pub fn relative(due: i64, now: i64) -> String {
+    let diff = due - now;
+    let amount = amount(diff.unsigned_abs());
+    if diff >= 0 { format!("in {amount}") } else { format!("{amount} overdue") }
+}
+
+/// How long ago `ts` was, e.g. "3d ago"
+pub fn ago(ts: i64, now: i64) -> String {
+    format!("{} ago", amount((now - ts).max(0) as u64))
+}
+
+fn amount(span: u64) -> String {
+    if span < 3600 {
+        format!("{}m", span.div_ceil(60).max(1))
+    } else if span < 48 * 3600 {
+        format!("{}h", span / 3600)
+    } else {
+        format!("{}d", span / 86_400)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    // Wednesday 2025-09-10 12:00 UTC
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 9, 10, 12, 0, 0).unwrap()
+    }
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> i64 {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap().timestamp()
+    }
+
+    #[test]
+    fn parses_placeholder_forms() {
+        let r = parse_timeframe("Today 3–5pm | 2025-09-10 09:00", &now()).unwrap();
+        assert_eq!(r, DueRange { start: at(2025, 9, 10, 15, 0), end: Some(at(2025, 9, 10, 17, 0)) });
+        let r = parse_timeframe("2025-09-12 09:00", &now()).unwrap();
+        assert_eq!(r.start, at(2025, 9, 12, 9, 0));
+    }
+
+    #[test]
+    fn parses_relative_and_weekday_forms() {
+        assert_eq!(parse_timeframe("in 3d", &now()).unwrap().start, at(2025, 9, 13, 12, 0));
+        assert_eq!(parse_timeframe("tomorrow 9am", &now()).unwrap().start, at(2025, 9, 11, 9, 0));
+        assert_eq!(parse_timeframe("fri", &now()).unwrap().start, at(2025, 9, 12, 23, 59) + 59);
+        assert_eq!(parse_timeframe("next wed 10:30", &now()).unwrap().start, at(2025, 9, 17, 10, 30));
+        assert!(parse_timeframe("whenever", &now()).is_none());
+        assert!(parse_timeframe("in 99999999d", &now()).is_none());
+        assert!(parse_timeframe("in 99999999999999999w", &now()).is_none());
+    }
+
+    #[test]
+    fn relative_text() {
+        assert_eq!(relative(7200, 0), "in 2h");
+        assert_eq!(relative(0, 3 * 86_400), "3d overdue");
+    }
+}
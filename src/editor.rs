@@ -0,0 +1,79 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    process::Command,
+};
+
+use anyhow::{bail, Context, Result};
+use uuid::Uuid;
+
+/// Editor to launch: `$VISUAL`, then `$EDITOR`, then `vi`
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|cmd| !cmd.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Open `initial` in `editor` through a temp file and wait for it to exit.
+/// Returns the new text, or `None` when the file was left unchanged.
+/// `editor` may carry arguments, e.g. `code --wait`.
+pub fn edit_text(editor: &str, initial: &str) -> Result<Option<String>> {
+    let mut words = editor.split_whitespace();
+    let Some(program) = words.next() else { bail!("no editor configured") };
+
+    let path = env::temp_dir().join(format!("todo-notes-{}.md", Uuid::new_v4()));
+    write_private(&path, initial).with_context(|| format!("writing {}", path.display()))?;
+    let status = Command::new(program).args(words).arg(&path).status();
+    let edited = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    let status = status.with_context(|| format!("could not start editor '{program}'"))?;
+    if !status.success() {
+        bail!("editor exited with {status}; notes left unchanged");
+    }
+    let edited = edited.context("reading the edited notes")?;
+    // editors usually add a final newline
+    let edited = edited.trim_end();
+    Ok((edited != initial.trim_end()).then(|| edited.to_string()))
+}
+
+/// Create `path` readable by the owner only (notes can be private) and write
+/// `text` to it; fails if the file already exists
+fn write_private(path: &Path, text: &str) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(text.as_bytes())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    #[test]
+    fn unchanged_file_and_failing_editor() {
+        assert_eq!(edit_text("true", "keep me\n").unwrap(), None);
+        assert!(edit_text("false", "keep me").is_err());
+
+        // an "editor" that rewrites the file, and notes down its permissions
+        let dir = env::temp_dir().join(format!("todo-editor-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("edit");
+        let mode = dir.join("mode");
+        let body = format!("#!/bin/sh\nls -l \"$1\" > {}\nprintf 'kept me\\n' > \"$1\"\n", mode.display());
+        fs::write(&script, body).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let changed = edit_text(&script.display().to_string(), "keep me").unwrap();
+        assert_eq!(changed.as_deref(), Some("kept me"));
+        assert!(fs::read_to_string(&mode).unwrap().starts_with("-rw-------"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
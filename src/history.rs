@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use crate::task::unix_now;
+use crate::todolist::TodoList;
+
+/// How many steps are kept in each direction
+pub const MAX_STEPS: usize = 50;
+
+/// One reversible change: the list as it was on the other side of it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Change {
+    pub label: String,
+    pub list: TodoList,
+}
+
+/// Undo/redo stacks of whole-list snapshots, shared by the TUI and the CLI
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    undo: Vec<Change>,
+    #[serde(default)]
+    redo: Vec<Change>,
+}
+
+impl History {
+    /// Remember `before` as the state prior to the change described by `label`.
+    /// A new change discards anything that could be redone.
+    pub fn record(&mut self, label: impl Into<String>, before: TodoList) {
+        self.undo.push(Change { label: label.into(), list: before });
+        if self.undo.len() > MAX_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Purge tasks trashed more than `days` ago (0 keeps them forever) as an
+    /// undoable step; returns how many were purged
+    pub fn auto_purge(&mut self, list: &mut TodoList, days: u32) -> usize {
+        if days == 0 {
+            return 0;
+        }
+        let before = list.clone();
+        let purged = list.purge_deleted_before(unix_now() - i64::from(days) * 86_400);
+        if purged > 0 {
+            self.record(format!("Purge {purged} task(s) trashed over {days} days ago"), before);
+        }
+        purged
+    }
+
+    /// Restore the state before the last change; returns its label
+    pub fn undo(&mut self, list: &mut TodoList) -> Option<String> {
+        Self::step(&mut self.undo, &mut self.redo, list)
+    }
+
+    /// Re-apply the last undone change; returns its label
+    pub fn redo(&mut self, list: &mut TodoList) -> Option<String> {
+        Self::step(&mut self.redo, &mut self.undo, list)
+    }
+
+    fn step(from: &mut Vec<Change>, to: &mut Vec<Change>, list: &mut TodoList) -> Option<String> {
+        let change = from.pop()?;
+        let current = std::mem::replace(list, change.list);
+        to.push(Change { label: change.label.clone(), list: current });
+        Some(change.label)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_and_redo_restore_snapshots() {
+        let mut list = TodoList::default();
+        let mut history = History::default();
+        history.record("Add \"A\"", list.clone());
+        list.add("A", 1, None);
+        history.record("Add \"B\"", list.clone());
+        list.add("B", 1, None);
+
+        assert_eq!(history.undo(&mut list).as_deref(), Some("Add \"B\""));
+        assert_eq!(list.len(), 1);
+        assert_eq!(history.redo(&mut list).as_deref(), Some("Add \"B\""));
+        assert_eq!(list.len(), 2);
+
+        history.undo(&mut list);
+        history.record("Add \"C\"", list.clone());
+        list.add("C", 1, None);
+        assert!(!history.can_redo());
+        history.undo(&mut list);
+        history.undo(&mut list);
+        assert!(list.is_empty() && history.undo(&mut list).is_none());
+    }
+
+    #[test]
+    fn auto_purge_is_an_undoable_step() {
+        let mut list = TodoList::default();
+        let mut history = History::default();
+        list.add("Old", 1, None);
+        list.delete_index(0);
+        list.trash[0].deleted_at = Some(unix_now() - 40 * 86_400);
+        assert_eq!(history.auto_purge(&mut list, 0), 0);
+        assert_eq!(history.auto_purge(&mut list, 60), 0);
+        assert!(!history.can_undo());
+
+        assert_eq!(history.auto_purge(&mut list, 30), 1);
+        assert!(list.trash.is_empty());
+        history.undo(&mut list);
+        assert_eq!(list.trash.len(), 1);
+    }
+}
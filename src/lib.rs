@@ -1,11 +1,21 @@
 pub mod task;
+pub mod due;
+pub mod recur;
 pub mod todolist;
+pub mod history;
 pub mod persistence;
 pub mod app;
+pub mod cli;
+pub mod config;
+pub mod editor;
 
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+use cli::format::{Field, ListFormat};
+use recur::Recurrence;
+use task::Status;
+
 /// CLI shared between main and tests
 #[derive(Parser, Debug)]
 #[command(name = "todo", version, about = "A small, styled Ratatui todo list")]
@@ -14,28 +24,157 @@ pub struct Cli {
     #[arg(long)]
     pub data_file: Option<PathBuf>,
 
+    /// Purge trashed tasks deleted more than this many days ago (0 keeps them)
+    #[arg(long, global = true, value_name = "DAYS")]
+    pub purge_after: Option<u32>,
+
+    /// Config file to read instead of the default (also `TODO_TUI_CONFIG`)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Override a config setting, e.g. `--set tick_rate_ms=200` (repeatable)
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+
     #[command(subcommand)]
     pub cmd: Option<Cmd>,
 }
 
+impl Cli {
+    /// Flags that override config settings, as `key=value` pairs after `--set`
+    pub fn config_overrides(&self) -> Vec<String> {
+        let mut overrides = self.overrides.clone();
+        if let Some(p) = &self.data_file {
+            overrides.push(format!("data_file={}", toml::Value::String(p.display().to_string())));
+        }
+        if let Some(days) = self.purge_after {
+            overrides.push(format!("purge_after_days={days}"));
+        }
+        overrides
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Cmd {
     /// Add a task quickly from the CLI
     Add {
+        /// Task title; inline `+tag` and `@project` tokens are extracted
         title: String,
         /// Priority (1 highest → larger = lower priority)
-        #[arg(short, long, default_value_t = 1)]
-        priority: i8,
+        #[arg(short, long)]
+        priority: Option<i8>,
         /// Optional notes
         #[arg(short, long)]
         notes: Option<String>,
+        /// Optional timeframe, e.g. "tomorrow 9am", "fri 3-5pm", "in 3d"
+        #[arg(short, long)]
+        timeframe: Option<String>,
+        /// Repeat rule, e.g. "daily", "every 2w", "mon,fri", "monthly 1", "after 3d"
+        #[arg(short, long)]
+        repeat: Option<Recurrence>,
+        /// Make this a subtask of the task with this id (or unique prefix)
+        #[arg(long)]
+        parent: Option<String>,
+        /// Task that must be done first (repeatable)
+        #[arg(long = "blocked-by")]
+        blocked_by: Vec<String>,
     },
     /// Print all tasks to stdout
-    List,
-    /// Toggle the 'done' status of a task by its visible index
-    Done { index: usize },
-    /// Delete a task by its visible index
-    Delete { index: usize },
+    List {
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = ListFormat::Table)]
+        format: ListFormat,
+        /// Comma-separated task fields to include, in order
+        #[arg(long, value_enum, value_delimiter = ',')]
+        fields: Vec<Field>,
+        /// Only tasks carrying this tag (repeatable; all must match)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Only tasks in this project
+        #[arg(long)]
+        project: Option<String>,
+    },
+    /// Toggle the 'done' status of a task by its id (a unique prefix is enough)
+    Done {
+        id: String,
+        /// Give all subtasks the same status
+        #[arg(short, long)]
+        recursive: bool,
+        /// Complete even if blocking tasks are still pending
+        #[arg(long)]
+        force: bool,
+    },
+    /// Set the lifecycle status of a task
+    Status {
+        id: String,
+        /// pending, in-progress, waiting, blocked, done or cancelled
+        status: Status,
+    },
+    /// Move a task right before another one in the manual order
+    Move {
+        id: String,
+        /// Sibling to place it in front of
+        #[arg(long, required = true)]
+        before: String,
+    },
+    /// Make a task wait for other tasks to be done
+    Block {
+        id: String,
+        /// Ids (or unique prefixes) of the blocking tasks
+        #[arg(long, required = true, num_args = 1..)]
+        on: Vec<String>,
+        /// Remove these dependencies instead of adding them
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Delete a task by its id (a unique prefix is enough)
+    Delete {
+        id: String,
+        /// Also delete all of its subtasks
+        #[arg(long, conflicts_with = "reparent")]
+        with_children: bool,
+        /// Keep its subtasks, moving them up to the deleted task's parent
+        #[arg(long)]
+        reparent: bool,
+    },
+    /// List, restore or purge deleted tasks
+    Trash {
+        #[command(subcommand)]
+        action: TrashCmd,
+    },
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCmd,
+    },
+    /// Revert the last change made from the CLI or the TUI
+    Undo,
+    /// Re-apply the last undone change
+    Redo,
     /// Launch the full-screen TUI
     Tui,
 }
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCmd {
+    /// Print the effective settings after merging the file, environment and flags
+    Show,
+    /// Print where the config file is read from
+    Path,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TrashCmd {
+    /// Show deleted tasks, newest first
+    List,
+    /// Bring a deleted task (and subtasks deleted with it) back
+    Restore { id: String },
+    /// Delete a trashed task for good
+    Purge {
+        #[arg(required_unless_present = "all")]
+        id: Option<String>,
+        /// Empty the whole trash
+        #[arg(long, conflicts_with = "id")]
+        all: bool,
+    },
+}
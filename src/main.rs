@@ -1,84 +1,187 @@
 use std::{
     io,
     path::Path,
+    process::ExitCode,
     time::{Duration, Instant},
 };
 use anyhow::Result;
+use clap::Parser;
 use crossterm::{
-    event::{self, Event},
+    cursor::Show,
+    event::{self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 
 use todo_tui::{
-    app::{input::handle_event, ui::draw, App},
-    persistence,
+    app::{input::handle_event, keymap::Keymap, theme::Theme, ui::draw, App},
+    cli, config::Config, editor, persistence, Cli, Cmd, ConfigCmd,
 };
 
-fn main() -> Result<()> {
-    let path = Path::new("todolist.json");
+fn main() -> ExitCode {
+    match run(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // A closed pipe (e.g. `todo list | head`) is not a failure
+        Err(e) if is_broken_pipe(&e) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn is_broken_pipe(e: &anyhow::Error) -> bool {
+    e.chain().any(|c| {
+        c.downcast_ref::<io::Error>().is_some_and(|io| io.kind() == io::ErrorKind::BrokenPipe)
+            || c.downcast_ref::<serde_json::Error>().and_then(|j| j.io_error_kind()) == Some(io::ErrorKind::BrokenPipe)
+    })
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let config_path = match &cli.config {
+        Some(p) => p.clone(),
+        None => match std::env::var_os("TODO_TUI_CONFIG") {
+            Some(p) => p.into(),
+            None => persistence::config_path()?,
+        },
+    };
+    let config = Config::load(&config_path, &cli.config_overrides())?;
+
+    if let Some(Cmd::Config { action }) = &cli.cmd {
+        match action {
+            ConfigCmd::Show => print!("# {}\n{}", config_path.display(), config.to_toml()?),
+            ConfigCmd::Path => println!("{}", config_path.display()),
+        }
+        return Ok(());
+    }
+
+    let path = config.data_path()?;
 
-    // Run the TUI
-    launch_tui(path)
+    // No subcommand means the TUI
+    match cli.cmd.unwrap_or(Cmd::Tui) {
+        Cmd::Tui => launch_tui(&path, &config),
+        mut cmd => {
+            if let Cmd::Add { priority, .. } = &mut cmd {
+                priority.get_or_insert(config.default_priority);
+            }
+            cli::run(cmd, &path, config.purge_after_days)
+        }
+    }
 }
 
-fn launch_tui(path: &Path) -> Result<()> {
+fn launch_tui(path: &Path, config: &Config) -> Result<()> {
     let list = persistence::load(path)?;
     let mut app = App::new(list);
+    app.apply_config(config);
+    app.theme = Theme::load(&config.theme, &persistence::themes_dir()?)?.apply_no_color();
+    app.keymap = Keymap::load(&persistence::keymap_path()?)?;
+    let conflicts = app.keymap.conflicts();
+    if !conflicts.is_empty() {
+        for c in &conflicts {
+            eprintln!("warning: key binding {c}");
+        }
+        app.status_line = format!("{} key binding conflict(s): {}", conflicts.len(), conflicts.join("; "));
+    }
+    app.history = persistence::load_history(path)?;
+    if app.history.auto_purge(&mut app.list, config.purge_after_days) > 0 {
+        persistence::save(path, &app.list)?;
+        persistence::save_history(path, &app.history)?;
+    }
+    app.sort = persistence::load_ui_state(path).sort;
+    let mut saved_sort = app.sort;
 
     // Terminal setup
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
     let _guard = TerminalGuard;
 
     // tick config
-    let tick_rate = Duration::from_millis(80);
+    let tick_rate = Duration::from_millis(config.tick_rate_ms);
     let mut last_tick = Instant::now();
+    let mut redraw = true;
 
-    // Main loop
+    // Main loop: draw only after input, a tick of a visible animation, or a clock minute
+    // passing (relative due times); an idle screen without animation sleeps until then
     loop {
-        terminal.draw(|f| draw(f, &app))?;
+        if redraw {
+            terminal.draw(|f| draw(f, &app))?;
+            redraw = false;
+        }
 
-        let timeout = tick_rate
-            .checked_sub(last_tick.elapsed())
-            .unwrap_or_else(|| Duration::from_secs(0));
+        let timeout = if app.animating() {
+            tick_rate.saturating_sub(last_tick.elapsed())
+        } else {
+            until_next_minute()
+        };
 
-        // poll with timeout so we also tick when there's no input
         if event::poll(timeout)? {
             let ev = event::read()?;
-            if let Event::Key(_) | Event::Mouse(_) | Event::Resize(_, _) = ev {
+            if let Event::Key(_) | Event::Mouse(_) | Event::Resize(_, _) | Event::Paste(_) = ev {
+                // plain pointer motion changes nothing on screen
+                redraw |= !matches!(ev, Event::Mouse(m) if m.kind == MouseEventKind::Moved);
                 let keep = handle_event(&mut app, ev);
-                if !keep {
-                    if app.dirty {
-                        persistence::save(path, &app.list)?;
-                        app.dirty = false;
-                    }
-                    break;
+                if let Some(id) = app.notes_editor.take() {
+                    edit_notes(&mut terminal, &mut app, &id)?;
                 }
                 if app.dirty {
                     persistence::save(path, &app.list)?;
+                    persistence::save_history(path, &app.history)?;
                     app.dirty = false;
                 }
+                if app.sort != saved_sort {
+                    persistence::save_ui_state(path, &persistence::UiState { sort: app.sort })?;
+                    saved_sort = app.sort;
+                }
+                if !keep {
+                    break;
+                }
             }
+        } else if !app.animating() {
+            // the minute turned over
+            redraw = true;
         }
 
-        if last_tick.elapsed() >= tick_rate {
+        if app.animating() && last_tick.elapsed() >= tick_rate {
             app.on_tick();
             last_tick = Instant::now();
+            redraw = true;
         }
     }
     Ok(())
 }
 
+/// Time left until the wall clock reaches the next whole minute
+fn until_next_minute() -> Duration {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    Duration::from_secs(60) - Duration::from_secs(now.as_secs() % 60) - Duration::from_nanos(now.subsec_nanos().into())
+}
+
+/// Suspend the TUI, edit the task's notes in the user's editor, then restore it
+fn edit_notes(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App, id: &str) -> Result<()> {
+    let Some(idx) = app.list.find_index_by_id(id) else { return Ok(()) };
+    let notes = app.list.items[idx].notes.clone().unwrap_or_default();
+
+    execute!(terminal.backend_mut(), DisableMouseCapture, DisableBracketedPaste, LeaveAlternateScreen, Show)?;
+    disable_raw_mode()?;
+    let edited = editor::edit_text(&editor::editor_command(), &notes);
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen, EnableBracketedPaste, EnableMouseCapture)?;
+    // the editor drew over the screen, so repaint everything
+    terminal.clear()?;
+
+    app.finish_notes_edit(id, edited);
+    Ok(())
+}
+
 struct TerminalGuard;
 impl Drop for TerminalGuard {
     fn drop(&mut self) {
         let _ = disable_raw_mode();
         let mut stdout = io::stdout();
-        let _ = execute!(stdout, LeaveAlternateScreen);
+        let _ = execute!(stdout, DisableMouseCapture, DisableBracketedPaste, LeaveAlternateScreen);
     }
 }
\ No newline at end of file
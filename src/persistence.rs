@@ -5,14 +5,34 @@ use std::{
 
 use anyhow::{anyhow, Result};
 use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
 
-use crate::todolist::TodoList;
+use crate::{
+    history::History,
+    todolist::{SortMode, TodoList},
+};
+
+fn project_dirs() -> Result<ProjectDirs> {
+    ProjectDirs::from("dev", "esravil", "todo-tui").ok_or_else(|| anyhow!("Cannot determine data directory"))
+}
 
 pub fn default_path() -> Result<PathBuf> {
-    let proj = ProjectDirs::from("dev", "esravil", "todo-tui")
-        .ok_or_else(|| anyhow!("Cannot determine data directory"))?;
-    let dir = proj.data_dir().to_path_buf();
-    Ok(dir.join("todos.json"))
+    Ok(project_dirs()?.data_dir().join("todos.json"))
+}
+
+/// User key bindings, e.g. `~/.config/todo-tui/keys.toml` on Linux
+pub fn keymap_path() -> Result<PathBuf> {
+    Ok(project_dirs()?.config_dir().join("keys.toml"))
+}
+
+/// `config.toml` in the config directory
+pub fn config_path() -> Result<PathBuf> {
+    Ok(project_dirs()?.config_dir().join("config.toml"))
+}
+
+/// Directory of user themes, one `<name>.toml` each
+pub fn themes_dir() -> Result<PathBuf> {
+    Ok(project_dirs()?.config_dir().join("themes"))
 }
 
 pub fn load(path: &Path) -> Result<TodoList> {
@@ -20,13 +40,61 @@ pub fn load(path: &Path) -> Result<TodoList> {
         return Ok(TodoList::default());
     }
     let bytes = fs::read(path)?;
-    let list = serde_json::from_slice::<TodoList>(&bytes)?;
+    let mut list = serde_json::from_slice::<TodoList>(&bytes)?;
+    list.backfill_due();
+    list.normalize_order();
     Ok(list)
 }
 
 pub fn save(path: &Path, list: &TodoList) -> Result<()> {
+    write_json(path, list)
+}
+
+/// Undo history lives next to the data file (`todos.json` → `todos.history.json`)
+pub fn history_path(path: &Path) -> PathBuf {
+    path.with_extension("history.json")
+}
+
+pub fn load_history(path: &Path) -> Result<History> {
+    let path = history_path(path);
+    if !path.exists() {
+        return Ok(History::default());
+    }
+    let bytes = fs::read(path)?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+pub fn save_history(path: &Path, history: &History) -> Result<()> {
+    write_json(&history_path(path), history)
+}
+
+/// View settings the TUI restores on start
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UiState {
+    #[serde(default)]
+    pub sort: SortMode,
+}
+
+/// `todos.json` → `todos.state.json`
+pub fn state_path(path: &Path) -> PathBuf {
+    path.with_extension("state.json")
+}
+
+/// Missing or unreadable state just means defaults
+pub fn load_ui_state(path: &Path) -> UiState {
+    fs::read(state_path(path))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_ui_state(path: &Path, state: &UiState) -> Result<()> {
+    write_json(&state_path(path), state)
+}
+
+fn write_json(path: &Path, value: &impl serde::Serialize) -> Result<()> {
     let tmp = path.with_extension("json.tmp");
-    let bytes = serde_json::to_vec_pretty(list)?;
+    let bytes = serde_json::to_vec_pretty(value)?;
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
     }
@@ -0,0 +1,278 @@
+//! Recurrence rules for repeating tasks.
+//!
+//! Rules are written and stored as short specs: `daily`, `weekly`, `every 3d`,
+//! `every 2w`, `mon,wed,fri`, `weekdays`, `monthly`, `monthly 15` and
+//! `after 3d` (three days after each completion).
+
+use std::{fmt, str::FromStr};
+
+use chrono::{
+    DateTime, Datelike, Days, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::due;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Recurrence {
+    /// Every N days from the previous due date
+    Days(u32),
+    /// Every N weeks from the previous due date
+    Weeks(u32),
+    /// On each listed weekday
+    Weekdays(Vec<Weekday>),
+    /// Monthly on a fixed day (the last day in shorter months), or on the due
+    /// date's day when `None`
+    Monthly(Option<u32>),
+    /// N days after the task is completed
+    AfterCompletion(u32),
+}
+
+impl Recurrence {
+    /// Next due time after a task due at `due` is completed at `now`.
+    /// Fixed schedules skip occurrences that are already in the past.
+    pub fn next_due<Tz: TimeZone>(&self, due: Option<i64>, now: &DateTime<Tz>) -> Option<i64> {
+        let tz = now.timezone();
+        let now_local = now.naive_local();
+        let anchor = anchor(due, now)?;
+
+        if let Recurrence::AfterCompletion(n) = self {
+            // keep the original time of day when there was one
+            let date = now_local.date().checked_add_days(Days::new((*n).into()))?;
+            let time = if due.is_some() { anchor.time() } else { NaiveTime::from_hms_opt(23, 59, 59)? };
+            return local_ts(&tz, date.and_time(time));
+        }
+
+        // pin the day so a clamped occurrence (Feb 28) doesn't move later ones
+        let rule = self.clone().pinned(due, now);
+        let mut next = rule.step(anchor)?;
+        while next <= now_local {
+            next = rule.step(next)?;
+        }
+        local_ts(&tz, next)
+    }
+
+    /// The rule to carry over to the next occurrence: plain `monthly` becomes
+    /// `monthly <day>` with the day of `due`, so short months don't drag it back
+    pub fn pinned<Tz: TimeZone>(self, due: Option<i64>, now: &DateTime<Tz>) -> Recurrence {
+        match (&self, anchor(due, now)) {
+            (Recurrence::Monthly(None), Some(anchor)) => Recurrence::Monthly(Some(anchor.day())),
+            _ => self,
+        }
+    }
+
+    /// One occurrence after `from`, keeping the time of day; `None` past the
+    /// end of the calendar
+    fn step(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        let date = from.date();
+        let next = match self {
+            Recurrence::Days(n) => date.checked_add_days(Days::new((*n).max(1).into()))?,
+            Recurrence::Weeks(n) => date.checked_add_days(Days::new(u64::from((*n).max(1)) * 7))?,
+            Recurrence::Weekdays(days) => (1..=7)
+                .filter_map(|k| date.checked_add_days(Days::new(k)))
+                .find(|d| days.contains(&d.weekday()))?,
+            Recurrence::Monthly(day) => {
+                let day = day.unwrap_or(date.day());
+                let this_month = date.with_day(1)?;
+                [this_month, this_month.checked_add_months(Months::new(1))?]
+                    .into_iter()
+                    .filter_map(|first| clamped_day(first, day))
+                    .find(|&d| d > date)?
+            }
+            Recurrence::AfterCompletion(n) => date.checked_add_days(Days::new((*n).into()))?,
+        };
+        Some(next.and_time(from.time()))
+    }
+}
+
+/// Where a schedule continues from: the due time, or `now` without one
+fn anchor<Tz: TimeZone>(due: Option<i64>, now: &DateTime<Tz>) -> Option<NaiveDateTime> {
+    match due {
+        Some(ts) => Some(now.timezone().timestamp_opt(ts, 0).single()?.naive_local()),
+        None => Some(now.naive_local()),
+    }
+}
+
+/// `day` of the month starting at `first`, or its last day when shorter
+fn clamped_day(first: NaiveDate, day: u32) -> Option<NaiveDate> {
+    let last = first.checked_add_months(Months::new(1))?.pred_opt()?.day();
+    first.with_day(day.min(last))
+}
+
+fn local_ts<Tz: TimeZone>(tz: &Tz, naive: NaiveDateTime) -> Option<i64> {
+    tz.from_local_datetime(&naive).earliest().map(|dt| dt.timestamp())
+}
+
+/// Render a due time back into timeframe text that `due::parse_timeframe` understands
+pub fn timeframe_for(start: i64, end: Option<i64>) -> String {
+    let fmt = |ts: i64| Local.timestamp_opt(ts, 0).single().map(|dt| dt.naive_local());
+    let Some(start) = fmt(start) else { return String::new() };
+    let mut text = if start.time() == NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default() {
+        start.format("%Y-%m-%d").to_string()
+    } else {
+        start.format("%Y-%m-%d %H:%M").to_string()
+    };
+    if let Some(end) = end.and_then(fmt) {
+        text.push_str(&end.format("-%H:%M").to_string());
+    }
+    text
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let spec = s.trim().to_lowercase();
+        let bad = || {
+            format!("invalid recurrence '{s}' (try daily, weekly, every 3d, every 2w, mon,fri, weekdays, monthly 15, after 3d)")
+        };
+
+        match spec.as_str() {
+            "daily" | "every day" => return Ok(Recurrence::Days(1)),
+            "weekly" | "every week" => return Ok(Recurrence::Weeks(1)),
+            "monthly" | "every month" => return Ok(Recurrence::Monthly(None)),
+            "weekdays" => {
+                return Ok(Recurrence::Weekdays(vec![
+                    Weekday::Mon,
+                    Weekday::Tue,
+                    Weekday::Wed,
+                    Weekday::Thu,
+                    Weekday::Fri,
+                ]));
+            }
+            _ => {}
+        }
+
+        if let Some(day) = spec.strip_prefix("monthly") {
+            let day: u32 = day.trim().parse().map_err(|_| bad())?;
+            return if (1..=31).contains(&day) { Ok(Recurrence::Monthly(Some(day))) } else { Err(bad()) };
+        }
+        if let Some(rest) = spec.strip_prefix("after") {
+            return parse_days(rest).map(Recurrence::AfterCompletion).ok_or_else(bad);
+        }
+
+        let rest = spec.strip_prefix("every").unwrap_or(&spec).trim();
+        let compact: String = rest.split_whitespace().collect();
+        if let Some(n) = compact.strip_suffix('w').or_else(|| compact.strip_suffix("weeks"))
+            && let Ok(n) = n.parse::<u32>()
+            && (1..=MAX_DAYS / 7).contains(&n)
+        {
+            return Ok(Recurrence::Weeks(n));
+        }
+        if let Some(n) = parse_days(rest) {
+            return Ok(Recurrence::Days(n));
+        }
+
+        let days: Option<Vec<Weekday>> =
+            compact.split(',').filter(|d| !d.is_empty()).map(due::parse_weekday).collect();
+        match days {
+            Some(days) if !days.is_empty() => Ok(Recurrence::Weekdays(days)),
+            _ => Err(bad()),
+        }
+    }
+}
+
+/// Longest interval a rule may have, about ten years
+const MAX_DAYS: u32 = 3660;
+
+/// `3d`, `3 days`; zero and more than `MAX_DAYS` are rejected
+fn parse_days(text: &str) -> Option<u32> {
+    let compact: String = text.split_whitespace().collect();
+    let n = compact.strip_suffix("days").or_else(|| compact.strip_suffix('d'))?;
+    n.parse::<u32>().ok().filter(|n| (1..=MAX_DAYS).contains(n))
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recurrence::Days(1) => write!(f, "daily"),
+            Recurrence::Days(n) => write!(f, "every {n}d"),
+            Recurrence::Weeks(1) => write!(f, "weekly"),
+            Recurrence::Weeks(n) => write!(f, "every {n}w"),
+            Recurrence::Weekdays(days) => {
+                let names: Vec<String> = days.iter().map(|d| d.to_string().to_lowercase()).collect();
+                write!(f, "{}", names.join(","))
+            }
+            Recurrence::Monthly(None) => write!(f, "monthly"),
+            Recurrence::Monthly(Some(d)) => write!(f, "monthly {d}"),
+            Recurrence::AfterCompletion(n) => write!(f, "after {n}d"),
+        }
+    }
+}
+
+impl TryFrom<String> for Recurrence {
+    type Error = String;
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Recurrence> for String {
+    fn from(r: Recurrence) -> Self {
+        r.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, Utc};
+
+    fn ymd(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn ts(d: NaiveDate, h: u32) -> i64 {
+        Utc.from_utc_datetime(&d.and_hms_opt(h, 0, 0).unwrap()).timestamp()
+    }
+
+    #[test]
+    fn specs_round_trip() {
+        for spec in ["daily", "every 3d", "weekly", "every 2w", "mon,wed,fri", "monthly", "monthly 31", "after 4d"] {
+            let r: Recurrence = spec.parse().unwrap();
+            assert_eq!(r.to_string(), spec);
+        }
+        assert!("every 0d".parse::<Recurrence>().is_err());
+        assert!("sometimes".parse::<Recurrence>().is_err());
+        assert!("every 999999999d".parse::<Recurrence>().is_err());
+        assert!("every 999999w".parse::<Recurrence>().is_err());
+        assert!("after 3661d".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn next_due_for_each_rule() {
+        // Wednesday 2025-09-10 09:00, completed at noon the same day
+        let due = Some(ts(ymd(2025, 9, 10), 9));
+        let now = Utc.from_utc_datetime(&ymd(2025, 9, 10).and_hms_opt(12, 0, 0).unwrap());
+
+        let next = |spec: &str| spec.parse::<Recurrence>().unwrap().next_due(due, &now).unwrap();
+        assert_eq!(next("every 2d"), ts(ymd(2025, 9, 12), 9));
+        assert_eq!(next("weekly"), ts(ymd(2025, 9, 17), 9));
+        assert_eq!(next("mon,fri"), ts(ymd(2025, 9, 12), 9));
+        assert_eq!(next("monthly 15"), ts(ymd(2025, 9, 15), 9));
+        assert_eq!(next("monthly 31"), ts(ymd(2025, 9, 30), 9));
+        assert_eq!(next("monthly 10"), ts(ymd(2025, 10, 10), 9));
+        assert_eq!(next("after 3d"), ts(ymd(2025, 9, 13), 9));
+
+        // past the end of the calendar there is no next occurrence
+        assert_eq!(Recurrence::Days(u32::MAX).next_due(due, &now), None);
+        assert_eq!(Recurrence::AfterCompletion(u32::MAX).next_due(due, &now), None);
+    }
+
+    #[test]
+    fn monthly_keeps_the_original_day_through_short_months() {
+        let mut due = Some(ts(ymd(2025, 1, 31), 9));
+        let mut rule: Recurrence = "monthly".parse().unwrap();
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            let now = Utc.timestamp_opt(due.unwrap(), 0).unwrap();
+            let next = rule.next_due(due, &now);
+            rule = rule.pinned(due, &now);
+            due = next;
+            seen.push(Utc.timestamp_opt(due.unwrap(), 0).unwrap().date_naive());
+        }
+        assert_eq!(seen, [ymd(2025, 2, 28), ymd(2025, 3, 31), ymd(2025, 4, 30)]);
+        assert_eq!(rule.to_string(), "monthly 31");
+    }
+}
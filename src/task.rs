@@ -1,11 +1,111 @@
+use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+use crate::due;
+use crate::recur::{self, Recurrence};
+
+/// Number of id characters shown by `todo list`
+pub const SHORT_ID_LEN: usize = 8;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Status {
     Pending,
+    InProgress,
+    Waiting,
+    Blocked,
     Done,
+    Cancelled,
+}
+
+impl Status {
+    pub const ALL: [Status; 6] = [
+        Status::Pending,
+        Status::InProgress,
+        Status::Waiting,
+        Status::Blocked,
+        Status::Done,
+        Status::Cancelled,
+    ];
+
+    /// Done and Cancelled end a task's life; everything else is still open
+    pub fn is_closed(self) -> bool {
+        matches!(self, Status::Done | Status::Cancelled)
+    }
+
+    /// Open states move freely between each other and can be closed.
+    /// Closed tasks can only be reopened (to Pending or In progress).
+    pub fn can_transition_to(self, next: Status) -> bool {
+        if self == next {
+            return false;
+        }
+        !self.is_closed() || matches!(next, Status::Pending | Status::InProgress)
+    }
+
+    /// Compact checkbox-style marker used in lists
+    pub fn mark(self) -> &'static str {
+        match self {
+            Status::Pending => "[ ]",
+            Status::InProgress => "[~]",
+            Status::Waiting => "[?]",
+            Status::Blocked => "[!]",
+            Status::Done => "[x]",
+            Status::Cancelled => "[-]",
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Status::Pending => "Pending",
+            Status::InProgress => "In progress",
+            Status::Waiting => "Waiting",
+            Status::Blocked => "Blocked",
+            Status::Done => "Done",
+            Status::Cancelled => "Cancelled",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "pending" | "todo" | "open" => Ok(Status::Pending),
+            "inprogress" | "started" | "doing" => Ok(Status::InProgress),
+            "waiting" | "wait" => Ok(Status::Waiting),
+            "blocked" => Ok(Status::Blocked),
+            "done" | "complete" | "completed" => Ok(Status::Done),
+            "cancelled" | "canceled" | "cancel" => Ok(Status::Cancelled),
+            _ => Err(format!(
+                "unknown status '{s}' (expected pending, in-progress, waiting, blocked, done or cancelled)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("cannot change status from {from} to {to}")]
+pub struct TransitionError {
+    pub from: Status,
+    pub to: Status,
+}
+
+/// Current time in unix seconds
+pub fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,39 +114,237 @@ pub struct Task {
     pub title: String,
     pub notes: Option<String>,
     pub timeframe: Option<String>, // NEW: optional human-readable time/timeframe
+    /// Parsed from `timeframe` (unix seconds); `None` when it couldn't be understood
+    #[serde(default)]
+    pub due: Option<i64>,
+    /// End of a time range such as "3–5pm"
+    #[serde(default)]
+    pub due_end: Option<i64>,
     pub priority: i8,
+    /// Position in the hand-arranged ("manual") order; lower comes first
+    #[serde(default)]
+    pub order: i64,
+    /// Free-form labels, written inline as `+tag`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Owning project, written inline as `@project`
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Id of the parent task when this is a subtask
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// Ids of tasks that must be done before this one can be
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+    /// Repeat rule; completing the task spawns the next occurrence
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
     pub status: Status,
     pub created_at: i64, // unix seconds
+    /// First time the task entered In progress
+    #[serde(default)]
+    pub started_at: Option<i64>,
+    /// When the task was last marked Done; cleared when reopened
+    #[serde(default)]
+    pub completed_at: Option<i64>,
+    /// Last status change or edit
+    #[serde(default)]
+    pub updated_at: Option<i64>,
+    /// When the task was moved to the trash
+    #[serde(default)]
+    pub deleted_at: Option<i64>,
+    /// Id of the subtree root when the task was trashed along with its
+    /// subtree, so a restore brings back that delete and nothing older
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_with: Option<String>,
 }
 
 impl Task {
     pub fn new(title: impl Into<String>, priority: i8, notes: Option<String>) -> Self {
         let title = title.into();
         let id = Uuid::new_v4().to_string();
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
+        let now = unix_now();
 
         Self {
             id,
             title,
             notes,
             timeframe: None,                  // NEW default
+            due: None,
+            due_end: None,
             priority: priority.clamp(1, 5),   // keep within 1..=5
+            order: 0,
+            tags: Vec::new(),
+            project: None,
+            parent_id: None,
+            blocked_by: Vec::new(),
+            recurrence: None,
             status: Status::Pending,
             created_at: now,
+            started_at: None,
+            completed_at: None,
+            updated_at: None,
+            deleted_at: None,
+            deleted_with: None,
+        }
+    }
+
+    /// Set the free-text timeframe and re-derive `due`/`due_end` from it
+    pub fn set_timeframe(&mut self, timeframe: Option<String>) {
+        self.set_timeframe_at(timeframe, &Local::now());
+    }
+
+    /// Like `set_timeframe`, resolving words like "today" relative to `now`
+    pub fn set_timeframe_at(&mut self, timeframe: Option<String>, now: &DateTime<Local>) {
+        let parsed = timeframe.as_deref().and_then(|tf| due::parse_timeframe(tf, now));
+        self.due = parsed.map(|r| r.start);
+        self.due_end = parsed.and_then(|r| r.end);
+        self.timeframe = timeframe;
+    }
+
+    /// Still open and past its due time
+    pub fn is_overdue(&self, now: i64) -> bool {
+        !self.is_closed() && self.due.is_some_and(|d| d < now)
+    }
+
+    /// Move to `next`, stamping the transition times. Closing a recurring task
+    /// returns its next occurrence, which takes over the recurrence rule.
+    pub fn set_status(&mut self, next: Status) -> Result<Option<Task>, TransitionError> {
+        if !self.status.can_transition_to(next) {
+            return Err(TransitionError { from: self.status, to: next });
+        }
+        let now = unix_now();
+        self.status = next;
+        self.updated_at = Some(now);
+        match next {
+            Status::InProgress => {
+                self.started_at.get_or_insert(now);
+            }
+            Status::Done => self.completed_at = Some(now),
+            _ => {}
+        }
+        if next != Status::Done {
+            self.completed_at = None;
         }
+        Ok(if next.is_closed() { self.next_occurrence(&Local::now()) } else { None })
     }
 
-    pub fn toggle_done(&mut self) {
-        self.status = match self.status {
-            Status::Pending => Status::Done,
-            Status::Done => Status::Pending,
+    /// Flip between done and open: Done and Cancelled reopen to Pending,
+    /// any other state completes. See `set_status` for the returned task.
+    #[must_use = "the next occurrence of a recurring task must be added to the list"]
+    pub fn toggle_done(&mut self) -> Option<Task> {
+        let next = if self.is_closed() { Status::Pending } else { Status::Done };
+        self.set_status(next).ok().flatten()
+    }
+
+    fn next_occurrence(&mut self, now: &DateTime<Local>) -> Option<Task> {
+        let rule = self.recurrence.take()?;
+        let Some(due) = rule.next_due(self.due, now) else {
+            self.recurrence = Some(rule);
+            return None;
         };
+        let due_end = self.due.zip(self.due_end).map(|(start, end)| due + (end - start));
+
+        let mut next = Task::new(self.title.clone(), self.priority, self.notes.clone());
+        next.tags = self.tags.clone();
+        next.project = self.project.clone();
+        next.parent_id = self.parent_id.clone();
+        next.timeframe = Some(recur::timeframe_for(due, due_end));
+        next.due = Some(due);
+        next.due_end = due_end;
+        next.recurrence = Some(rule.pinned(self.due, now));
+        Some(next)
+    }
+
+    /// Leading characters of the id, enough to address the task from the CLI
+    pub fn short_id(&self) -> &str {
+        match self.id.char_indices().nth(SHORT_ID_LEN) {
+            Some((end, _)) => &self.id[..end],
+            None => &self.id,
+        }
     }
 
     pub fn is_done(&self) -> bool {
         self.status == Status::Done
     }
+
+    pub fn is_closed(&self) -> bool {
+        self.status.is_closed()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// A title with its inline `+tag` / `@project` tokens pulled out
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct InlineTokens {
+    pub title: String,
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+}
+
+/// Split `+tag` and `@project` tokens out of a typed title.
+/// The first `@project` wins; repeated tags are kept once.
+pub fn parse_inline_tokens(raw: &str) -> InlineTokens {
+    let mut out = InlineTokens::default();
+    let mut words: Vec<&str> = Vec::new();
+    for word in raw.split_whitespace() {
+        if let Some(tag) = word.strip_prefix('+').filter(|t| !t.is_empty()) {
+            if !out.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                out.tags.push(tag.to_string());
+            }
+        } else if let Some(project) = word.strip_prefix('@').filter(|p| !p.is_empty()) {
+            out.project.get_or_insert_with(|| project.to_string());
+        } else {
+            words.push(word);
+        }
+    }
+    out.title = words.join(" ");
+    out
+}
+
+/// The title with its project and tags written back as inline tokens,
+/// so it can be edited and parsed again
+pub fn inline_title(task: &Task) -> String {
+    let mut out = task.title.clone();
+    if let Some(p) = &task.project {
+        out.push_str(&format!(" @{p}"));
+    }
+    for tag in &task.tags {
+        out.push_str(&format!(" +{tag}"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_tokens_are_extracted() {
+        let parsed = parse_inline_tokens("Ship +release report @work +urgent +Release @home");
+        assert_eq!(parsed.title, "Ship report");
+        assert_eq!(parsed.tags, vec!["release", "urgent"]);
+        assert_eq!(parsed.project.as_deref(), Some("work"));
+
+        let mut t = Task::new(parsed.title, 1, None);
+        t.tags = parsed.tags;
+        t.project = parsed.project;
+        assert_eq!(parse_inline_tokens(&inline_title(&t)), parse_inline_tokens("Ship report @work +release +urgent"));
+    }
+
+    #[test]
+    fn status_transitions_stamp_times() {
+        let mut t = Task::new("Lifecycle", 1, None);
+        t.set_status(Status::InProgress).unwrap();
+        let started = t.started_at.expect("started");
+        t.set_status(Status::Waiting).unwrap();
+        t.set_status(Status::Done).unwrap();
+        assert!(t.completed_at.is_some() && t.started_at == Some(started));
+        assert!(t.set_status(Status::Cancelled).is_err());
+        t.set_status(Status::Pending).unwrap();
+        assert!(t.completed_at.is_none() && t.updated_at.is_some());
+    }
 }
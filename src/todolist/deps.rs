@@ -0,0 +1,98 @@
+use thiserror::Error;
+
+use super::TodoList;
+
+/// Why a dependency could not be added
+#[derive(Debug, Error)]
+pub enum DependencyError {
+    #[error("a task cannot block itself")]
+    SelfDependency,
+    #[error("dependency would create a cycle: {}", .0.join(" → "))]
+    Cycle(Vec<String>),
+}
+
+/// "B can't start until A is done", stored as ids in `Task::blocked_by`.
+/// Ids that no longer exist in the list are ignored.
+impl TodoList {
+    /// Record that the task at `idx` waits for the task at `blocker`
+    pub fn add_dependency(&mut self, idx: usize, blocker: usize) -> Result<(), DependencyError> {
+        if idx == blocker {
+            return Err(DependencyError::SelfDependency);
+        }
+        if let Some(path) = self.dependency_path(blocker, idx) {
+            // blocker already waits (transitively) on idx; show the loop as "waits on" steps
+            let titles = std::iter::once(idx).chain(path).map(|i| self.items[i].title.clone()).collect();
+            return Err(DependencyError::Cycle(titles));
+        }
+        let blocker_id = self.items[blocker].id.clone();
+        let deps = &mut self.items[idx].blocked_by;
+        if !deps.contains(&blocker_id) {
+            deps.push(blocker_id);
+        }
+        Ok(())
+    }
+
+    /// Drop `blocker` from the dependencies of `idx`; returns whether it was there
+    pub fn remove_dependency(&mut self, idx: usize, blocker: usize) -> bool {
+        let blocker_id = self.items[blocker].id.clone();
+        let deps = &mut self.items[idx].blocked_by;
+        let before = deps.len();
+        deps.retain(|d| *d != blocker_id);
+        deps.len() != before
+    }
+
+    /// Chain of indices from `from` to `to` following `blocked_by`, if one exists
+    fn dependency_path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut prev: Vec<Option<usize>> = vec![None; self.items.len()];
+        let mut seen = vec![false; self.items.len()];
+        let mut stack = vec![from];
+        seen[from] = true;
+        while let Some(i) = stack.pop() {
+            if i == to {
+                let mut path = vec![i];
+                let mut cur = i;
+                while let Some(p) = prev[cur] {
+                    path.push(p);
+                    cur = p;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for b in self.blockers(i) {
+                if !seen[b] {
+                    seen[b] = true;
+                    prev[b] = Some(i);
+                    stack.push(b);
+                }
+            }
+        }
+        None
+    }
+
+    /// Upstream tasks the task at `idx` waits for
+    pub fn blockers(&self, idx: usize) -> Vec<usize> {
+        self.items[idx].blocked_by.iter().filter_map(|id| self.find_index_by_id(id)).collect()
+    }
+
+    /// Upstream tasks that are still open; a cancelled blocker no longer holds anything up
+    pub fn pending_blockers(&self, idx: usize) -> Vec<usize> {
+        self.blockers(idx).into_iter().filter(|&b| !self.items[b].is_closed()).collect()
+    }
+
+    pub fn is_blocked(&self, idx: usize) -> bool {
+        !self.pending_blockers(idx).is_empty()
+    }
+
+    /// Downstream tasks waiting for the task at `idx`
+    pub fn dependents(&self, idx: usize) -> Vec<usize> {
+        let id = &self.items[idx].id;
+        (0..self.items.len()).filter(|&i| self.items[i].blocked_by.contains(id)).collect()
+    }
+
+    /// Remove references to purged tasks
+    pub(super) fn forget_dependencies(&mut self, ids: &[String]) {
+        for t in self.items.iter_mut().chain(&mut self.trash) {
+            t.blocked_by.retain(|d| !ids.contains(d));
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::task::{Status, Task};
+
+/// Narrows a task list by tag, project, status, priority and a fuzzy text query;
+/// shared by the TUI and `todo list`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TaskFilter {
+    /// Every listed tag must be present
+    pub tags: Vec<String>,
+    pub project: Option<String>,
+    pub status: Option<StatusFilter>,
+    /// Inclusive priority range, e.g. `(1, 2)` for P1–P2
+    pub priority: Option<(i8, i8)>,
+    /// Fuzzy search over title, notes and timeframe
+    pub query: String,
+}
+
+/// Quick status toggles in the Todos tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusFilter {
+    /// Anything not yet done or cancelled
+    Pending,
+    Done,
+}
+
+impl TaskFilter {
+    /// Build a filter, accepting values typed with or without the inline `+` / `@` sigils
+    pub fn new(tags: Vec<String>, project: Option<String>) -> Self {
+        Self {
+            tags: tags.iter().map(|t| t.trim_start_matches('+').to_string()).collect(),
+            project: project.map(|p| p.trim_start_matches('@').to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.project.is_none()
+            && self.status.is_none()
+            && self.priority.is_none()
+            && self.query.trim().is_empty()
+    }
+
+    pub fn matches(&self, t: &Task) -> bool {
+        let project_ok = match &self.project {
+            Some(p) => t.project.as_deref().is_some_and(|tp| tp.eq_ignore_ascii_case(p)),
+            None => true,
+        };
+        let status_ok = match self.status {
+            Some(StatusFilter::Pending) => !t.is_closed(),
+            Some(StatusFilter::Done) => t.status == Status::Done,
+            None => true,
+        };
+        let priority_ok = self.priority.is_none_or(|(lo, hi)| (lo..=hi).contains(&t.priority));
+        project_ok && status_ok && priority_ok && self.tags.iter().all(|tag| t.has_tag(tag)) && self.matches_query(t)
+    }
+
+    /// Every word of the query fuzzily matches the title, notes or timeframe
+    fn matches_query(&self, t: &Task) -> bool {
+        let fields = [Some(t.title.as_str()), t.notes.as_deref(), t.timeframe.as_deref()];
+        self.query
+            .split_whitespace()
+            .all(|word| fields.iter().flatten().any(|f| fuzzy_match(word, f).is_some()))
+    }
+
+    /// Char positions in `text` to highlight for the current query
+    pub fn highlights(&self, text: &str) -> Vec<usize> {
+        let mut out: Vec<usize> = self.query.split_whitespace().filter_map(|w| fuzzy_match(w, text)).flatten().collect();
+        out.sort_unstable();
+        out.dedup();
+        out
+    }
+
+    /// Short label such as `+work @home /rep pending P1-2`, empty when nothing is filtered
+    pub fn describe(&self) -> String {
+        let mut parts: Vec<String> = self.tags.iter().map(|t| format!("+{t}")).collect();
+        if let Some(p) = &self.project {
+            parts.push(format!("@{p}"));
+        }
+        if !self.query.trim().is_empty() {
+            parts.push(format!("/{}", self.query.trim()));
+        }
+        match self.status {
+            Some(StatusFilter::Pending) => parts.push("pending".into()),
+            Some(StatusFilter::Done) => parts.push("done".into()),
+            None => {}
+        }
+        if let Some((lo, hi)) = self.priority {
+            parts.push(if lo == hi { format!("P{lo}") } else { format!("P{lo}-{hi}") });
+        }
+        parts.join(" ")
+    }
+}
+
+/// Case-insensitive subsequence match: every char of `query` appears in `text`
+/// in order. Returns the char positions matched in `text`.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<Vec<usize>> {
+    let mut wanted = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut hits = Vec::new();
+    for (i, c) in text.chars().enumerate() {
+        let Some(&w) = wanted.peek() else { break };
+        if c.to_lowercase().eq(std::iter::once(w)) {
+            hits.push(i);
+            wanted.next();
+        }
+    }
+    wanted.peek().is_none().then_some(hits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_query_and_quick_filters() {
+        assert_eq!(fuzzy_match("wrt", "Write report"), Some(vec![0, 1, 3]));
+        assert_eq!(fuzzy_match("xyz", "Write report"), None);
+
+        let mut t = Task::new("Write report", 2, Some("quarterly numbers".into()));
+        t.timeframe = Some("friday".into());
+        let mut f = TaskFilter { query: "rep fri".into(), ..Default::default() };
+        assert!(f.matches(&t));
+        f.query = "qrtly".into();
+        assert!(f.matches(&t));
+
+        f.priority = Some((1, 1));
+        assert!(!f.matches(&t));
+        f.priority = Some((1, 3));
+        f.status = Some(StatusFilter::Done);
+        assert!(!f.matches(&t));
+        f.status = Some(StatusFilter::Pending);
+        assert!(f.matches(&t));
+        assert_eq!(f.describe(), "/qrtly pending P1-3");
+    }
+}
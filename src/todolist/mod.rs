@@ -1,30 +1,143 @@
+mod deps;
+pub mod filter;
+mod sort;
+mod trash;
+mod tree;
+
+use chrono::{Local, TimeZone};
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::task::{Status, Task, TransitionError};
+
+pub use deps::DependencyError;
+pub use filter::{StatusFilter, TaskFilter};
+pub use sort::{SortKey, SortMode};
 
-use crate::task::{Status, Task};
+/// Why a status change was refused
+#[derive(Debug, Error)]
+pub enum StatusError {
+    #[error("no such task")]
+    NoSuchTask,
+    #[error(transparent)]
+    Transition(#[from] TransitionError),
+    #[error("blocked by pending task(s): {}", .0.join(", "))]
+    Blocked(Vec<String>),
+}
+
+/// Why an id prefix could not be resolved to exactly one task
+#[derive(Debug, Error)]
+pub enum IdLookupError {
+    #[error("task id cannot be empty")]
+    Empty,
+    #[error("no task id starts with '{0}'")]
+    NotFound(String),
+    #[error("id prefix '{prefix}' is ambiguous, candidates:{}", format_candidates(.candidates))]
+    Ambiguous { prefix: String, candidates: Vec<(String, String)> },
+}
+
+/// Find the task whose id is `prefix`, or the only one starting with it
+fn resolve_in(tasks: &[Task], prefix: &str) -> Result<usize, IdLookupError> {
+    let prefix = prefix.trim().to_ascii_lowercase();
+    if prefix.is_empty() {
+        return Err(IdLookupError::Empty);
+    }
+    if let Some(idx) = tasks.iter().position(|t| t.id == prefix) {
+        return Ok(idx);
+    }
+    let matches: Vec<&Task> = tasks.iter().filter(|t| t.id.starts_with(&prefix)).collect();
+    match matches.as_slice() {
+        [] => Err(IdLookupError::NotFound(prefix)),
+        [one] => Ok(tasks.iter().position(|t| t.id == one.id).expect("matched task is in the list")),
+        many => Err(IdLookupError::Ambiguous {
+            candidates: many.iter().map(|t| (t.short_id().to_string(), t.title.clone())).collect(),
+            prefix,
+        }),
+    }
+}
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+fn format_candidates(candidates: &[(String, String)]) -> String {
+    candidates
+        .iter()
+        .map(|(id, title)| format!("\n  {id}  {title}"))
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TodoList {
     pub items: Vec<Task>,
+    /// Deleted tasks, kept until restored or purged
+    #[serde(default)]
+    pub trash: Vec<Task>,
 }
 
 impl TodoList {
-    pub fn add(&mut self, title: &str, priority: i8, notes: Option<String>) {
-        self.items.push(Task::new(title, priority, notes));
-        // self.sort();
+    pub fn add(&mut self, title: &str, priority: i8, notes: Option<String>) -> &mut Task {
+        self.push(Task::new(title, priority, notes));
+        self.items.last_mut().expect("just pushed")
+    }
+
+    /// Append a task at the end of the manual order
+    fn push(&mut self, mut task: Task) {
+        task.order = self.next_order();
+        self.items.push(task);
+    }
+
+    /// Parse timeframes of tasks saved before `due` existed, relative to their creation time
+    pub fn backfill_due(&mut self) {
+        for t in &mut self.items {
+            if t.due.is_none() && t.timeframe.is_some() {
+                let created = Local.timestamp_opt(t.created_at, 0).single().unwrap_or_else(Local::now);
+                t.set_timeframe_at(t.timeframe.clone(), &created);
+            }
+        }
     }
 
+    /// Move one task to the trash; its children move up to its own parent
     pub fn delete_index(&mut self, idx: usize) -> bool {
         if idx < self.items.len() {
-            self.items.remove(idx);
+            let removed = self.trash_index(idx, None);
+            for t in &mut self.items {
+                if t.parent_id.as_deref() == Some(removed.id.as_str()) {
+                    t.parent_id = removed.parent_id.clone();
+                }
+            }
             true
         } else {
             false
         }
     }
 
+    /// Toggle done; completing a recurring task appends its next occurrence.
+    /// Refuses to complete a task whose blockers are still pending.
     pub fn toggle_done_index(&mut self, idx: usize) -> bool {
+        if idx < self.items.len() && !self.items[idx].is_closed() && self.is_blocked(idx) {
+            return false;
+        }
+        self.force_toggle_done_index(idx)
+    }
+
+    /// Move a task to `status`; closing a recurring task appends its next occurrence
+    pub fn set_status_index(&mut self, idx: usize, status: Status) -> Result<(), StatusError> {
+        let Some(task) = self.items.get(idx) else { return Err(StatusError::NoSuchTask) };
+        if status == Status::Done && !task.is_closed() {
+            let blockers = self.pending_blockers(idx);
+            if !blockers.is_empty() {
+                return Err(StatusError::Blocked(blockers.iter().map(|&b| self.items[b].title.clone()).collect()));
+            }
+        }
+        if let Some(next) = self.items[idx].set_status(status)? {
+            self.push(next);
+        }
+        Ok(())
+    }
+
+    /// Toggle done even when blockers remain
+    pub fn force_toggle_done_index(&mut self, idx: usize) -> bool {
         if let Some(t) = self.items.get_mut(idx) {
-            t.toggle_done();
+            if let Some(next) = t.toggle_done() {
+                self.push(next);
+            }
             true
         } else {
             false
@@ -35,10 +148,33 @@ impl TodoList {
         self.items.iter().position(|t| t.id == id)
     }
 
-    /// Sort by priority ascending (1 = highest), then newest first
-    pub fn sort(&mut self) {
-        self.items
-            .sort_by(|a, b| a.priority.cmp(&b.priority).then(b.created_at.cmp(&a.created_at)));
+    /// Resolve a full id or a unique id prefix (like a git short hash) to an index
+    pub fn resolve_id_prefix(&self, prefix: &str) -> Result<usize, IdLookupError> {
+        resolve_in(&self.items, prefix)
+    }
+
+    /// Distinct tags across all tasks, sorted case-insensitively
+    pub fn all_tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.items.iter().flat_map(|t| &t.tags) {
+            if !tags.iter().any(|seen| seen.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags
+    }
+
+    /// Distinct projects across all tasks, sorted case-insensitively
+    pub fn all_projects(&self) -> Vec<String> {
+        let mut projects: Vec<String> = Vec::new();
+        for p in self.items.iter().filter_map(|t| t.project.as_ref()) {
+            if !projects.iter().any(|seen| seen.eq_ignore_ascii_case(p)) {
+                projects.push(p.clone());
+            }
+        }
+        projects.sort_by_key(|p| p.to_lowercase());
+        projects
     }
 
     pub fn len(&self) -> usize {
@@ -49,18 +185,24 @@ impl TodoList {
         self.items.is_empty()
     }
 
+    /// Tasks that are not Done or Cancelled
     pub fn active_count(&self) -> usize {
-        self.items.iter().filter(|t| t.status == Status::Pending).count()
+        self.items.iter().filter(|t| !t.is_closed()).count()
     }
 
     pub fn done_count(&self) -> usize {
-        self.items.iter().filter(|t| t.status == Status::Done).count()
+        self.count_status(Status::Done)
+    }
+
+    pub fn count_status(&self, status: Status) -> usize {
+        self.items.iter().filter(|t| t.status == status).count()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::task::unix_now;
 
     #[test]
     fn add_and_toggle() {
@@ -71,4 +213,162 @@ mod tests {
         t.toggle_done_index(0);
         assert!(matches!(t.items[0].status, Status::Done));
     }
+
+    #[test]
+    fn cancelled_tasks_are_not_active() {
+        let mut t = TodoList::default();
+        t.add("Keep", 1, None);
+        t.add("Drop", 1, None);
+        t.add("Start", 1, None);
+        t.set_status_index(1, Status::Cancelled).unwrap();
+        t.set_status_index(2, Status::InProgress).unwrap();
+        assert_eq!(t.active_count(), 2);
+        assert_eq!(t.count_status(Status::Cancelled), 1);
+        assert_eq!(t.rolled_up_progress(), 0.0);
+        t.toggle_done_index(0);
+        assert_eq!(t.rolled_up_progress(), 0.5);
+    }
+
+    #[test]
+    fn sort_modes_order_tasks() {
+        let mut t = TodoList::default();
+        t.add("beta", 3, None).due = Some(200);
+        t.add("Alpha", 1, None);
+        t.add("gamma", 2, None).due = Some(100);
+        let titles = |key, descending| {
+            let order = t.tree_order_by(SortMode { key, descending });
+            order.iter().map(|&(i, _)| t.items[i].title.as_str()).collect::<Vec<_>>().join(",")
+        };
+
+        assert_eq!(titles(SortKey::Title, false), "Alpha,beta,gamma");
+        assert_eq!(titles(SortKey::Priority, true), "beta,gamma,Alpha");
+        // undated tasks stay last in both directions
+        assert_eq!(titles(SortKey::Due, true), "beta,gamma,Alpha");
+        assert_eq!(titles(SortKey::Due, false), "gamma,beta,Alpha");
+        // sorting only changes the view
+        assert_eq!(t.items[0].title, "beta");
+    }
+
+    #[test]
+    fn manual_order_moves_among_siblings() {
+        let mut t = TodoList::default();
+        for title in ["a", "b", "c"] {
+            t.add(title, 1, None);
+        }
+        t.add("child", 1, None).parent_id = Some(t.items[0].id.clone());
+        let titles = |t: &TodoList| t.tree_order().iter().map(|&(i, _)| t.items[i].title.as_str()).collect::<Vec<_>>().join(",");
+
+        assert!(t.move_by(2, -1));
+        assert_eq!(titles(&t), "a,child,c,b");
+        assert!(!t.move_by(0, -1));
+        assert!(t.move_before(1, 0));
+        assert_eq!(titles(&t), "b,a,child,c");
+        // a subtask can't be moved among the top-level tasks
+        assert!(!t.move_before(3, 0));
+    }
+
+    #[test]
+    fn completing_recurring_task_spawns_next() {
+        let mut t = TodoList::default();
+        t.add("Standup notes", 2, None);
+        t.items[0].recurrence = Some("daily".parse().unwrap());
+        t.toggle_done_index(0);
+        assert_eq!(t.len(), 2);
+        assert!(t.items[0].recurrence.is_none());
+        assert_eq!(t.items[1].title, "Standup notes");
+        assert_eq!(t.items[1].status, Status::Pending);
+        assert!(t.items[1].recurrence.is_some() && t.items[1].due.is_some());
+    }
+
+    #[test]
+    fn tree_order_progress_and_deletes() {
+        let mut t = TodoList::default();
+        let root = t.add("Release", 1, None).id.clone();
+        let build = t.add("Build", 1, None).id.clone();
+        t.items[1].parent_id = Some(root.clone());
+        t.add("Unrelated", 1, None);
+        t.add("Compile", 1, None).parent_id = Some(build.clone());
+        t.add("Notes", 1, None).parent_id = Some(root.clone());
+
+        let order: Vec<(usize, usize)> = t.tree_order();
+        assert_eq!(order, vec![(0, 0), (1, 1), (3, 2), (4, 1), (2, 0)]);
+
+        t.toggle_done_index(4);
+        assert_eq!(t.progress(0), 0.5);
+        assert_eq!(t.rolled_up_progress(), 0.25);
+
+        // deleting "Build" re-parents "Compile" onto "Release"
+        t.delete_index(1);
+        assert_eq!(t.items[2].parent_id.as_deref(), Some(root.as_str()));
+        assert_eq!(t.delete_tree(0), 3);
+        assert_eq!(t.len(), 1);
+    }
+
+    #[test]
+    fn trash_restores_subtrees_and_purges() {
+        let mut t = TodoList::default();
+        let root = t.add("Release", 1, None).id.clone();
+        t.add("Build", 1, None).parent_id = Some(root.clone());
+        t.add("Ship", 1, None);
+        t.add_dependency(2, 0).unwrap(); // Ship waits on Release
+
+        assert_eq!(t.delete_tree(0), 2);
+        assert_eq!((t.len(), t.trash.len()), (1, 2));
+        assert!(t.trash.iter().all(|x| x.deleted_at.is_some()));
+        assert!(!t.is_blocked(0));
+
+        let idx = t.resolve_trash_prefix(&root).unwrap();
+        assert_eq!(t.restore(idx), 2);
+        let build = t.items.iter().find(|x| x.title == "Build").unwrap();
+        assert_eq!(build.parent_id.as_deref(), Some(root.as_str()));
+        assert!(t.is_blocked(0) && t.trash.is_empty());
+
+        // purging forgets the dependency for good
+        t.delete_index(t.find_index_by_id(&root).unwrap());
+        assert_eq!(t.purge_deleted_before(unix_now() + 1), 1);
+        assert!(t.items[0].blocked_by.is_empty());
+
+        // a subtask trashed earlier on its own stays in the trash
+        let root = t.add("Docs", 1, None).id.clone();
+        t.add("Draft", 1, None).parent_id = Some(root.clone());
+        t.add("Review", 1, None).parent_id = Some(root.clone());
+        t.delete_index(t.find_index_by_id(&root).unwrap() + 1);
+        assert_eq!(t.delete_tree(t.find_index_by_id(&root).unwrap()), 2);
+        assert_eq!(t.restore(t.resolve_trash_prefix(&root).unwrap()), 2);
+        assert_eq!(t.trash.len(), 1);
+        assert_eq!(t.trash[0].title, "Draft");
+    }
+
+    #[test]
+    fn dependencies_block_completion_and_reject_cycles() {
+        let mut t = TodoList::default();
+        t.add("A", 1, None);
+        t.add("B", 1, None);
+        t.add("C", 1, None);
+        t.add_dependency(1, 0).unwrap(); // B waits on A
+        t.add_dependency(2, 1).unwrap(); // C waits on B
+
+        let err = t.add_dependency(0, 2).unwrap_err();
+        assert_eq!(err.to_string(), "dependency would create a cycle: A → C → B → A");
+
+        assert!(!t.toggle_done_index(1));
+        assert!(!t.items[1].is_done());
+        t.toggle_done_index(0);
+        assert!(t.toggle_done_index(1));
+        assert_eq!(t.dependents(1), vec![2]);
+    }
+
+    #[test]
+    fn resolve_id_prefix_unique_ambiguous_unknown() {
+        let mut t = TodoList::default();
+        t.add("First", 1, None);
+        t.add("Second", 1, None);
+        t.items[0].id = "abc12345-0000".into();
+        t.items[1].id = "abd99999-0000".into();
+
+        assert_eq!(t.resolve_id_prefix("abc").unwrap(), 0);
+        assert_eq!(t.resolve_id_prefix("ABD9").unwrap(), 1);
+        assert!(matches!(t.resolve_id_prefix("ab"), Err(IdLookupError::Ambiguous { candidates, .. }) if candidates.len() == 2));
+        assert!(matches!(t.resolve_id_prefix("zz"), Err(IdLookupError::NotFound(_))));
+    }
 }
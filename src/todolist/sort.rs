@@ -0,0 +1,147 @@
+use std::{cmp::Ordering, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use super::TodoList;
+use crate::task::{Status, Task};
+
+/// What the Todos table is ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// Hand-arranged `Task::order`
+    #[default]
+    Manual,
+    Priority,
+    Created,
+    Due,
+    Title,
+    Status,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 6] =
+        [SortKey::Manual, SortKey::Priority, SortKey::Created, SortKey::Due, SortKey::Title, SortKey::Status];
+
+    pub fn next(self) -> SortKey {
+        let i = Self::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// A sort key plus direction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortMode {
+    pub key: SortKey,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+impl SortMode {
+    /// Order two tasks; tasks without a due time always go last
+    pub fn compare(self, a: &Task, b: &Task) -> Ordering {
+        let ord = match self.key {
+            SortKey::Manual => a.order.cmp(&b.order),
+            SortKey::Priority => a.priority.cmp(&b.priority),
+            SortKey::Created => a.created_at.cmp(&b.created_at),
+            SortKey::Due => match (a.due, b.due) {
+                (Some(x), Some(y)) => x.cmp(&y),
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            SortKey::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            SortKey::Status => status_rank(a.status).cmp(&status_rank(b.status)),
+        };
+        if self.descending { ord.reverse() } else { ord }
+    }
+}
+
+fn status_rank(s: Status) -> usize {
+    Status::ALL.iter().position(|&x| x == s).unwrap_or(0)
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self.key {
+            SortKey::Manual => "manual",
+            SortKey::Priority => "priority",
+            SortKey::Created => "created",
+            SortKey::Due => "due",
+            SortKey::Title => "title",
+            SortKey::Status => "status",
+        };
+        write!(f, "{name} {}", if self.descending { "↓" } else { "↑" })
+    }
+}
+
+/// Manual order: each task's `order` is only compared with its siblings,
+/// since `tree_order` always keeps subtasks under their parent.
+impl TodoList {
+    pub(super) fn next_order(&self) -> i64 {
+        self.items.iter().map(|t| t.order + 1).max().unwrap_or(0)
+    }
+
+    /// Give every task a distinct `order` (files from before it existed have
+    /// all zeros), keeping the current manual order
+    pub fn normalize_order(&mut self) {
+        let mut by_order: Vec<usize> = (0..self.items.len()).collect();
+        by_order.sort_by_key(|&i| self.items[i].order);
+        if by_order.windows(2).all(|w| self.items[w[0]].order < self.items[w[1]].order) {
+            return;
+        }
+        for (pos, i) in by_order.into_iter().enumerate() {
+            self.items[i].order = pos as i64;
+        }
+    }
+
+    /// Make the order `mode` shows the manual order
+    pub fn adopt_order(&mut self, mode: SortMode) {
+        for (pos, (i, _)) in self.tree_order_by(mode).into_iter().enumerate() {
+            self.items[i].order = pos as i64;
+        }
+    }
+
+    /// Swap the task with its previous (`delta < 0`) or next sibling in manual order
+    pub fn move_by(&mut self, idx: usize, delta: isize) -> bool {
+        let mut siblings = self.siblings_in_order(idx);
+        let Some(pos) = siblings.iter().position(|&i| i == idx) else { return false };
+        let Some(to) = pos.checked_add_signed(delta).filter(|&to| to < siblings.len()) else {
+            return false;
+        };
+        siblings.swap(pos, to);
+        self.renumber(&siblings);
+        true
+    }
+
+    /// Place the task right before `target` in manual order; both must share a parent
+    pub fn move_before(&mut self, idx: usize, target: usize) -> bool {
+        if idx == target || self.parent_index(idx) != self.parent_index(target) {
+            return false;
+        }
+        let mut siblings = self.siblings_in_order(idx);
+        siblings.retain(|&i| i != idx);
+        let at = siblings.iter().position(|&i| i == target).expect("target is a sibling");
+        siblings.insert(at, idx);
+        self.renumber(&siblings);
+        true
+    }
+
+    /// Tasks sharing the parent of `idx` (including itself), in manual order
+    fn siblings_in_order(&mut self, idx: usize) -> Vec<usize> {
+        self.normalize_order();
+        let parent = self.parent_index(idx);
+        let mut siblings: Vec<usize> = (0..self.items.len()).filter(|&i| self.parent_index(i) == parent).collect();
+        siblings.sort_by_key(|&i| self.items[i].order);
+        siblings
+    }
+
+    /// Hand the group's existing `order` values out again in the sequence given
+    fn renumber(&mut self, sequence: &[usize]) {
+        let mut values: Vec<i64> = sequence.iter().map(|&i| self.items[i].order).collect();
+        values.sort_unstable();
+        for (&i, v) in sequence.iter().zip(values) {
+            self.items[i].order = v;
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use super::{IdLookupError, TodoList};
+use crate::task::{unix_now, Task};
+
+/// Soft delete: deleted tasks wait in `TodoList::trash`, stamped with
+/// `deleted_at`, until they are restored or purged for good.
+/// Their `blocked_by` links are kept so a restore brings them back.
+impl TodoList {
+    /// Move the task at `idx` to the trash, as part of deleting the subtree
+    /// rooted at `subtree` when there is one
+    pub(super) fn trash_index(&mut self, idx: usize, subtree: Option<&str>) -> Task {
+        let mut task = self.items.remove(idx);
+        task.deleted_at = Some(unix_now());
+        task.deleted_with = subtree.map(str::to_string);
+        self.trash.push(task.clone());
+        task
+    }
+
+    /// Put a trashed task back, along with the subtasks deleted together with it.
+    /// Returns how many tasks came back.
+    pub fn restore(&mut self, trash_idx: usize) -> usize {
+        let group = self.trash_subtree(trash_idx);
+        let first = self.items.len();
+        for &i in group.iter().rev() {
+            let mut task = self.trash.remove(i);
+            task.deleted_at = None;
+            task.deleted_with = None;
+            self.items.push(task);
+        }
+        for i in first..self.items.len() {
+            // a parent that is gone (or still trashed) can't hold it any more
+            let parent = self.items[i].parent_id.as_deref();
+            if parent.is_some_and(|p| self.find_index_by_id(p).is_none()) {
+                self.items[i].parent_id = None;
+            }
+        }
+        self.normalize_order();
+        group.len()
+    }
+
+    /// Delete a trashed task for good, with the subtasks deleted together with it
+    pub fn purge(&mut self, trash_idx: usize) -> usize {
+        let group = self.trash_subtree(trash_idx);
+        let ids: Vec<String> = group.iter().rev().map(|&i| self.trash.remove(i).id).collect();
+        self.forget_dependencies(&ids);
+        ids.len()
+    }
+
+    pub fn empty_trash(&mut self) -> usize {
+        let ids: Vec<String> = self.trash.drain(..).map(|t| t.id).collect();
+        self.forget_dependencies(&ids);
+        ids.len()
+    }
+
+    /// Purge everything deleted before `cutoff` (unix seconds)
+    pub fn purge_deleted_before(&mut self, cutoff: i64) -> usize {
+        let (old, keep): (Vec<Task>, Vec<Task>) = std::mem::take(&mut self.trash)
+            .into_iter()
+            .partition(|t| t.deleted_at.is_some_and(|d| d < cutoff));
+        self.trash = keep;
+        let ids: Vec<String> = old.into_iter().map(|t| t.id).collect();
+        self.forget_dependencies(&ids);
+        ids.len()
+    }
+
+    /// Resolve a full id or unique prefix among trashed tasks
+    pub fn resolve_trash_prefix(&self, prefix: &str) -> Result<usize, IdLookupError> {
+        super::resolve_in(&self.trash, prefix)
+    }
+
+    /// `trash_idx` plus trashed tasks under it that went in the same subtree
+    /// delete, ascending; subtasks trashed on their own stay put
+    fn trash_subtree(&self, trash_idx: usize) -> Vec<usize> {
+        let mut group = vec![trash_idx];
+        let Some(subtree) = &self.trash[trash_idx].deleted_with else { return group };
+        let mut i = 0;
+        while i < group.len() {
+            let id = &self.trash[group[i]].id;
+            let children = (0..self.trash.len()).filter(|&c| {
+                let t = &self.trash[c];
+                t.parent_id.as_ref() == Some(id) && t.deleted_with.as_ref() == Some(subtree)
+            });
+            group.extend(children.collect::<Vec<_>>());
+            i += 1;
+        }
+        group.sort_unstable();
+        group.dedup();
+        group
+    }
+}
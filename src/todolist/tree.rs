@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use super::TodoList;
+use super::SortMode;
+use crate::task::Status;
+
+/// Parent/child relationships expressed through `Task::parent_id`.
+///
+/// Tasks whose parent is missing (or that sit in a parent cycle from a
+/// hand-edited file) are treated as roots, so every task is always reachable.
+impl TodoList {
+    /// Child indices of every task plus the root indices, both in list order
+    fn child_map(&self) -> (Vec<Vec<usize>>, Vec<usize>) {
+        let by_id: HashMap<&str, usize> =
+            self.items.iter().enumerate().map(|(i, t)| (t.id.as_str(), i)).collect();
+        let mut kids = vec![Vec::new(); self.items.len()];
+        let mut roots = Vec::new();
+        for (i, t) in self.items.iter().enumerate() {
+            match t.parent_id.as_deref().and_then(|p| by_id.get(p)) {
+                Some(&p) if p != i => kids[p].push(i),
+                _ => roots.push(i),
+            }
+        }
+        (kids, roots)
+    }
+
+    /// Depth-first order of the whole tree as `(index, depth)` pairs, siblings in manual order
+    pub fn tree_order(&self) -> Vec<(usize, usize)> {
+        self.tree_order_by(SortMode::default())
+    }
+
+    /// Like `tree_order`, with each group of siblings sorted by `mode`; `items` is left alone
+    pub fn tree_order_by(&self, mode: SortMode) -> Vec<(usize, usize)> {
+        let (mut kids, mut roots) = self.child_map();
+        let by_mode = |a: &usize, b: &usize| mode.compare(&self.items[*a], &self.items[*b]);
+        roots.sort_by(by_mode);
+        for k in &mut kids {
+            k.sort_by(by_mode);
+        }
+        let mut seen = vec![false; self.items.len()];
+        let mut out = Vec::with_capacity(self.items.len());
+
+        fn walk(i: usize, depth: usize, kids: &[Vec<usize>], seen: &mut [bool], out: &mut Vec<(usize, usize)>) {
+            if seen[i] {
+                return;
+            }
+            seen[i] = true;
+            out.push((i, depth));
+            for &k in &kids[i] {
+                walk(k, depth + 1, kids, seen, out);
+            }
+        }
+
+        for r in roots {
+            walk(r, 0, &kids, &mut seen, &mut out);
+        }
+        // members of a parent cycle are never reached from a root
+        for i in 0..self.items.len() {
+            walk(i, 0, &kids, &mut seen, &mut out);
+        }
+        out
+    }
+
+    /// Index of the parent task, if it exists in the list
+    pub fn parent_index(&self, idx: usize) -> Option<usize> {
+        let parent = self.items.get(idx)?.parent_id.as_deref()?;
+        self.find_index_by_id(parent).filter(|&p| p != idx)
+    }
+
+    /// Direct children of the task at `idx`, in list order
+    pub fn children(&self, idx: usize) -> Vec<usize> {
+        let Some(id) = self.items.get(idx).map(|t| t.id.as_str()) else { return Vec::new() };
+        (0..self.items.len())
+            .filter(|&i| i != idx && self.items[i].parent_id.as_deref() == Some(id))
+            .collect()
+    }
+
+    pub fn has_children(&self, idx: usize) -> bool {
+        !self.children(idx).is_empty()
+    }
+
+    /// All tasks below `idx`, depth first
+    pub fn descendants(&self, idx: usize) -> Vec<usize> {
+        let (kids, _) = self.child_map();
+        let mut out = Vec::new();
+        let mut stack: Vec<usize> = kids.get(idx).cloned().unwrap_or_default();
+        stack.reverse();
+        while let Some(i) = stack.pop() {
+            if i == idx || out.contains(&i) {
+                continue;
+            }
+            out.push(i);
+            stack.extend(kids[i].iter().rev());
+        }
+        out
+    }
+
+    /// Completion of one task from 0 to 1. A done task counts fully; an open
+    /// parent counts as the average progress of its children. Cancelled
+    /// subtasks are left out of the average.
+    pub fn progress(&self, idx: usize) -> f64 {
+        let (kids, _) = self.child_map();
+        let mut visiting = vec![false; self.items.len()];
+        self.progress_in(idx, &kids, &mut visiting)
+    }
+
+    fn progress_in(&self, idx: usize, kids: &[Vec<usize>], visiting: &mut [bool]) -> f64 {
+        if self.items[idx].status == Status::Done {
+            return 1.0;
+        }
+        let children: Vec<usize> = kids[idx]
+            .iter()
+            .copied()
+            .filter(|&k| !visiting[k] && self.items[k].status != Status::Cancelled)
+            .collect();
+        if children.is_empty() {
+            return 0.0;
+        }
+        visiting[idx] = true;
+        let sum: f64 = children.iter().map(|&k| self.progress_in(k, kids, visiting)).sum();
+        visiting[idx] = false;
+        sum / children.len() as f64
+    }
+
+    /// Average progress of the root tasks, so subtasks count towards their parent.
+    /// Cancelled tasks are ignored.
+    pub fn rolled_up_progress(&self) -> f64 {
+        let (kids, mut roots) = self.child_map();
+        roots.retain(|&r| self.items[r].status != Status::Cancelled);
+        if roots.is_empty() {
+            return 0.0;
+        }
+        let mut visiting = vec![false; self.items.len()];
+        let sum: f64 = roots.iter().map(|&r| self.progress_in(r, &kids, &mut visiting)).sum();
+        sum / roots.len() as f64
+    }
+
+    /// Toggle a task and give every descendant the same status
+    pub fn toggle_done_cascade(&mut self, idx: usize) -> bool {
+        let below = self.descendants(idx);
+        if !self.toggle_done_index(idx) {
+            return false;
+        }
+        self.sync_status(idx, &below);
+        true
+    }
+
+    /// Complete every open descendant when `idx` is done, or reopen the done
+    /// ones when it is not. Cancelled subtasks are left alone.
+    pub fn cascade_status(&mut self, idx: usize) {
+        let below = self.descendants(idx);
+        self.sync_status(idx, &below);
+    }
+
+    fn sync_status(&mut self, idx: usize, below: &[usize]) {
+        let done = self.items[idx].is_done();
+        for &i in below {
+            let status = self.items[i].status;
+            if (done && !status.is_closed()) || (!done && status == Status::Done) {
+                self.force_toggle_done_index(i);
+            }
+        }
+    }
+
+    /// Move a task and all of its descendants to the trash; returns how many were removed
+    pub fn delete_tree(&mut self, idx: usize) -> usize {
+        if idx >= self.items.len() {
+            return 0;
+        }
+        let mut doomed = self.descendants(idx);
+        doomed.push(idx);
+        doomed.sort_unstable();
+        let subtree = self.items[idx].id.clone();
+        for &i in doomed.iter().rev() {
+            self.trash_index(i, Some(&subtree));
+        }
+        doomed.len()
+    }
+}